@@ -2,13 +2,435 @@
 
 use std::io;
 use std::io::Write;
+use std::path::PathBuf;
 use std::process::exit;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::ValueEnum;
 
 #[derive(Debug, Default, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+// allow because these are all independent command-line flags
 pub struct MusicOpts {
     pub verbose: bool,
     pub ratings: bool,
     pub easter: bool,
+    pub truncate: bool,
+    pub marquee: Option<usize>,
+    pub table: bool,
+    pub group: bool,
+    pub next: bool,
+    pub audio: bool,
+    pub columns: Vec<Column>,
+    pub tick: Duration,
+    pub bar: BarStyle,
+    pub art: ArtMode,
+    pub music_dir: Option<PathBuf>,
+    pub lyrics_dir: Option<PathBuf>,
+    pub output: Output,
+    pub hooks: Vec<Hook>,
+    pub hook_timeout: Duration,
+    pub binds: Vec<Bind>,
+    pub layout: Layout,
+}
+
+// how we show things, the fullscreen ui or something for other programs
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Output {
+    #[default]
+    Full,
+    // one line from a template, printed once or on every change
+    Oneline {
+        template: String,
+        follow: bool,
+    },
+    // a json object on every change
+    Json,
+    // status bar protocols, with a line of text from a template
+    I3bar {
+        template: String,
+    },
+    Waybar {
+        template: String,
+    },
+    // plain sentences when things change, for screen readers
+    Accessible,
+}
+
+// everything a key can do. the doc comments double as the help text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Action {
+    /// Show/hide this help
+    Help,
+    /// Quit
+    Quit,
+    /// Pause/play
+    Toggle,
+    /// Previous track
+    Prev,
+    /// Next track
+    Next,
+    /// Seek back 10 seconds
+    SeekBack,
+    /// Seek ahead 10 seconds
+    SeekAhead,
+    /// Stop playback
+    Stop,
+    /// Volume up
+    VolumeUp,
+    /// Volume down
+    VolumeDown,
+    /// Crossfade up
+    CrossfadeUp,
+    /// Crossfade down
+    CrossfadeDown,
+    /// Toggle repeat
+    Repeat,
+    /// Toggle random
+    Random,
+    /// Toggle single
+    Single,
+    /// Toggle consume
+    Consume,
+    /// Shuffle (reorders queue in-place)
+    Shuffle,
+    /// Raise current track rating
+    RateUp,
+    /// Lower current track rating
+    RateDown,
+    /// Clear playback error
+    ClearError,
+    /// Show/hide lyrics
+    Lyrics,
+    /// Switch to the next screen layout
+    Layout,
+    /// Say what's playing and where, in full
+    WhereAmI,
+    /// Scroll down
+    ScrollDown,
+    /// Scroll up
+    ScrollUp,
+    /// Scroll down a page
+    PageDown,
+    /// Scroll up a page
+    PageUp,
+    /// Collapse/expand album group
+    Collapse,
+    /// Browse the library by artist and album
+    Library,
+    /// Search the library
+    Search,
+    /// Browse the music directory
+    Files,
+    /// Show the playing song in the music directory
+    Locate,
+    /// Manage stored playlists
+    Playlists,
+    /// Save the queue as a new playlist
+    SavePlaylist,
+    /// Rename the selected playlist
+    Rename,
+    /// Delete a playlist, or remove a song from one
+    Remove,
+    /// Move a song up in a playlist
+    MoveUp,
+    /// Move a song down in a playlist
+    MoveDown,
+    /// Open what's selected
+    Open,
+    /// Go back up, or close
+    Back,
+    /// Add the selection to the queue
+    Add,
+    /// Add everything in the list to the queue
+    AddAll,
+    /// Insert the selection after the current song
+    Insert,
+    /// Replace the queue with the selection, and play it
+    Replace,
+    /// Find a song in the queue
+    Find,
+    /// Go to the next song found
+    NextMatch,
+    /// Go to the previous song found
+    PrevMatch,
+}
+
+// the actions that only talk to the server, so they work without a screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Click {
+    Toggle,
+    Prev,
+    Next,
+    SeekBack,
+    SeekAhead,
+    Stop,
+    VolumeUp,
+    VolumeDown,
+    CrossfadeUp,
+    CrossfadeDown,
+    Repeat,
+    Random,
+    Single,
+    Consume,
+    Shuffle,
+    RateUp,
+    RateDown,
+}
+
+impl From<Click> for Action {
+    fn from(click: Click) -> Self {
+        match click {
+            Click::Toggle => Self::Toggle,
+            Click::Prev => Self::Prev,
+            Click::Next => Self::Next,
+            Click::SeekBack => Self::SeekBack,
+            Click::SeekAhead => Self::SeekAhead,
+            Click::Stop => Self::Stop,
+            Click::VolumeUp => Self::VolumeUp,
+            Click::VolumeDown => Self::VolumeDown,
+            Click::CrossfadeUp => Self::CrossfadeUp,
+            Click::CrossfadeDown => Self::CrossfadeDown,
+            Click::Repeat => Self::Repeat,
+            Click::Random => Self::Random,
+            Click::Single => Self::Single,
+            Click::Consume => Self::Consume,
+            Click::Shuffle => Self::Shuffle,
+            Click::RateUp => Self::RateUp,
+            Click::RateDown => Self::RateDown,
+        }
+    }
+}
+
+// a key press, after escape sequences have been read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Backspace,
+    Tab,
+    BackTab,
+    Esc,
+}
+
+// names for keys that don't print as themselves
+const KEY_NAMES: [(&str, Key); 14] = [
+    ("space", Key::Char(' ')),
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("pgup", Key::PageUp),
+    ("pgdn", Key::PageDown),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("enter", Key::Enter),
+    ("backspace", Key::Backspace),
+    ("tab", Key::Tab),
+    ("shift-tab", Key::BackTab),
+    ("esc", Key::Esc),
+];
+
+impl Key {
+    #[must_use]
+    pub fn name(self) -> String {
+        KEY_NAMES.iter().find(|(_, k)| *k == self).map_or_else(
+            || match self {
+                Self::Char(c) => c.to_string(),
+                _ => format!("{self:?}").to_lowercase(),
+            },
+            |(n, _)| (*n).into(),
+        )
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    // a single character, or one of the names above
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(Self::Char(c)),
+            _ => KEY_NAMES
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(s))
+                .map(|(_, k)| *k)
+                .ok_or_else(|| format!("unknown key: {s}")),
+        }
+    }
+}
+
+// a key bound to an action by the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bind {
+    pub key: Key,
+    pub action: Action,
+}
+
+impl FromStr for Bind {
+    type Err = String;
+
+    // parses things like "n=next", "space=toggle" or "==volume-up"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, action) = s
+            .rsplit_once('=')
+            .ok_or_else(|| format!("expected KEY=ACTION: {s}"))?;
+        Ok(Self {
+            key: key.parse()?,
+            action: Action::from_str(action.trim(), true)?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    pub width: ColumnWidth,
+    pub align: Align,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnWidth {
+    #[default]
+    Auto,
+    Fixed(usize),
+    Percent(usize),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    // parses things like "auto", "20", "<30%" or ">12"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (align, width) = match s.chars().next() {
+            Some('<') => (Align::Left, &s[1..]),
+            Some('^') => (Align::Center, &s[1..]),
+            Some('>') => (Align::Right, &s[1..]),
+            _ => (Align::default(), s),
+        };
+
+        let err = || format!("invalid column width: {s}");
+        let width = match width {
+            "" | "auto" => ColumnWidth::Auto,
+            w => w.strip_suffix('%').map_or_else(
+                || w.parse().map(ColumnWidth::Fixed).map_err(|_| err()),
+                |p| match p.parse() {
+                    Ok(p) if p <= 100 => Ok(ColumnWidth::Percent(p)),
+                    _ => Err(err()),
+                },
+            )?,
+        };
+
+        Ok(Self { width, align })
+    }
+}
+
+// a command to run when something happens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hook {
+    pub event: Event,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Event {
+    /// A different song started
+    Song,
+    /// Playback started or resumed
+    Play,
+    /// Playback paused
+    Pause,
+    /// Playback stopped
+    Stop,
+    /// The queue changed
+    Queue,
+    /// The current song's rating changed
+    Rating,
+    /// The volume changed
+    Volume,
+    /// The last song in the queue finished
+    End,
+}
+
+impl FromStr for Hook {
+    type Err = String;
+
+    // parses things like "song=notify-send hi"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (event, command) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected EVENT=COMMAND: {s}"))?;
+        let event = Event::from_str(event.trim(), true)?;
+        Ok(Self {
+            event,
+            command: command.into(),
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BarStyle {
+    /// [===>   ]
+    #[default]
+    Ascii,
+    /// Smooth fill using eighth blocks
+    Blocks,
+    /// Fine-grained fill using braille dots
+    Braille,
+    /// Thin line with a knob
+    Line,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Layout {
+    /// Header on top, queue below
+    #[default]
+    Top,
+    /// Queue on top, header below
+    Bottom,
+    /// Just the header
+    Header,
+    /// Just the queue
+    Queue,
+    /// Header and queue side by side, for wide terminals
+    Split,
+    /// The current song, big and centered
+    Big,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ArtMode {
+    /// Don't show album art
+    #[default]
+    None,
+    /// Pick the best method the terminal supports
+    Auto,
+    /// Kitty graphics protocol
+    Kitty,
+    /// Sixel graphics
+    Sixel,
+    /// Colored half-blocks
+    Blocks,
+    /// Plain text
+    Ascii,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -40,3 +462,42 @@ pub fn clean_exit(exitcode: ExitCode) -> ! {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(s: &str) -> Result<(ColumnWidth, Align), String> {
+        s.parse::<Column>().map(|c| (c.width, c.align))
+    }
+
+    #[test]
+    fn column_widths() {
+        assert_eq!(column("auto"), Ok((ColumnWidth::Auto, Align::Left)));
+        assert_eq!(column(""), Ok((ColumnWidth::Auto, Align::Left)));
+        assert_eq!(column("20"), Ok((ColumnWidth::Fixed(20), Align::Left)));
+        assert_eq!(column("30%"), Ok((ColumnWidth::Percent(30), Align::Left)));
+        assert_eq!(
+            column(" 100% "),
+            Ok((ColumnWidth::Percent(100), Align::Left))
+        );
+    }
+
+    #[test]
+    fn column_alignments() {
+        assert_eq!(column(">12"), Ok((ColumnWidth::Fixed(12), Align::Right)));
+        assert_eq!(
+            column("^50%"),
+            Ok((ColumnWidth::Percent(50), Align::Center))
+        );
+        assert_eq!(column("<"), Ok((ColumnWidth::Auto, Align::Left)));
+        assert_eq!(column(">auto"), Ok((ColumnWidth::Auto, Align::Right)));
+    }
+
+    #[test]
+    fn bad_columns() {
+        for bad in ["101%", "-3", "wide", "%", "12%%", ">>3", "3.5"] {
+            assert!(column(bad).is_err(), "{bad} should be an error");
+        }
+    }
+}
@@ -1,16 +1,24 @@
 pub mod common;
 mod player;
 
-use common::MusicOpts;
+use common::{
+    ArtMode, BarStyle, Bind, Click, Column, Hook, Layout, MusicOpts, Output,
+};
 use player::Player;
 
 use std::env;
+use std::path::PathBuf;
 use std::string::ToString;
+use std::time::Duration;
 
 use clap::Parser;
 
 const DEFAULT_HOST: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 6600;
+#[allow(clippy::literal_string_with_formatting_args)]
+// allow because this is a template, not a format string
+const DEFAULT_ONELINE: &str =
+    "{state} {title} * {artist} {elapsed}/{duration} {ersc} {volume}%";
 
 fn main() {
     let args = Args::parse();
@@ -18,18 +26,14 @@ fn main() {
     println!("{args:?}");
 
     // get argument vars
-    let host = args.host.map_or_else(
-        || env::var("MPD_HOST").unwrap_or_else(|_| DEFAULT_HOST.to_string()),
-        |h| h,
-    );
-    let port = args.port.map_or_else(
-        || {
-            env::var("MPD_PORT").map_or(DEFAULT_PORT, |p| {
-                p.parse().expect("invalid value for port")
-            })
-        },
-        |p| p,
-    );
+    let host = args.host.unwrap_or_else(|| {
+        env::var("MPD_HOST").unwrap_or_else(|_| DEFAULT_HOST.to_string())
+    });
+    let port = args.port.unwrap_or_else(|| {
+        env::var("MPD_PORT").map_or(DEFAULT_PORT, |p| {
+            p.parse().expect("invalid value for port")
+        })
+    });
     let address = format!("{host}:{port}");
     let format = if args.title {
         vec!["title".into()]
@@ -41,10 +45,47 @@ fn main() {
         })
     };
 
+    if let Some(click) = args.click {
+        return Player::click(&address, click.into());
+    }
+
+    let output = if args.accessible {
+        Output::Accessible
+    } else if args.json {
+        Output::Json
+    } else if let Some(template) = args.i3bar {
+        Output::I3bar { template }
+    } else if let Some(template) = args.waybar {
+        Output::Waybar { template }
+    } else {
+        args.oneline
+            .map_or(Output::Full, |template| Output::Oneline {
+                template,
+                follow: args.follow,
+            })
+    };
+
     let options = MusicOpts {
         verbose: args.verbose,
         ratings: !args.no_ratings,
         easter: args.easter,
+        truncate: args.truncate,
+        marquee: args.marquee.map(usize::from),
+        table: args.table,
+        group: args.group,
+        next: args.next,
+        audio: args.audio,
+        columns: args.columns.unwrap_or_default(),
+        tick: Duration::from_millis(args.tick),
+        bar: args.bar,
+        art: args.art,
+        music_dir: args.music_dir,
+        lyrics_dir: args.lyrics_dir,
+        output,
+        hooks: args.hook,
+        hook_timeout: Duration::from_secs(args.hook_timeout),
+        binds: args.bind,
+        layout: args.layout,
     };
 
     Player::init(&address, format, options);
@@ -78,6 +119,139 @@ struct Args {
     #[arg(short = 'R', long = "no-ratings")]
     no_ratings: bool,
 
+    /// Cut long queue entries short with an ellipsis instead of wrapping
+    #[arg(long)]
+    truncate: bool,
+
+    /// Keep header lines to one row, sliding long ones along by <COLS>
+    /// columns every tick
+    #[arg(
+        long,
+        value_name = "COLS",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    marquee: Option<u16>,
+
+    /// Show the queue as a table, with a column for each format field
+    #[arg(short = 'T', long)]
+    table: bool,
+
+    /// Group the queue by album, with a line at the top of each
+    #[arg(short = 'G', long, conflicts_with = "table")]
+    group: bool,
+
+    /// Comma-separated list of table column widths: <N>, <N>%, or auto.
+    /// Prefix with '<', '^' or '>' to align left, center or right
+    #[arg(long, value_delimiter = ',')]
+    columns: Option<Vec<Column>>,
+
+    /// Show what's playing next under the header
+    #[arg(short = 'N', long)]
+    next: bool,
+
+    /// Show the audio format and bitrate under the header
+    #[arg(short = 'A', long)]
+    audio: bool,
+
+    /// Milliseconds between redraws while playing
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(50..))]
+    tick: u64,
+
+    /// How to arrange the screen. 'v' switches between them
+    #[arg(long, value_enum, default_value_t)]
+    layout: Layout,
+
+    /// Style of the progress bar
+    #[arg(long, value_enum, default_value_t)]
+    bar: BarStyle,
+
+    /// Show album art next to the header
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        num_args = 0..=1,
+        default_missing_value = "auto"
+    )]
+    art: ArtMode,
+
+    /// Music directory of the server, to find lyrics next to songs
+    #[arg(long)]
+    music_dir: Option<PathBuf>,
+
+    /// Directory of lyrics files named '<ARTIST> - <TITLE>.lrc'
+    /// [default: $XDG_CACHE_HOME/mpc-display-rs/lyrics]
+    #[arg(long)]
+    lyrics_dir: Option<PathBuf>,
+
+    /// Run a shell command on an event, as <EVENT>=<COMMAND>. Events are
+    /// song, play, pause, stop, queue, rating, volume and end. Can be
+    /// given more than once
+    #[arg(long, value_name = "EVENT=COMMAND")]
+    hook: Vec<Hook>,
+
+    /// Seconds a hook can run before it gets killed
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    hook_timeout: u64,
+
+    /// Bind a key to an action, as <KEY>=<ACTION>. Keys are a character
+    /// or a name like space, up or pgdn. Press '?' to see the actions.
+    /// Can be given more than once
+    #[arg(long, value_name = "KEY=ACTION")]
+    bind: Vec<Bind>,
+
+    /// Print the status on one line and exit, for status bars.
+    /// <TEMPLATE> fields look like {title} or {volume:>3}
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        group = "output",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_ONELINE
+    )]
+    oneline: Option<String>,
+
+    /// With --oneline, print a new line whenever anything changes
+    #[arg(long, requires = "oneline")]
+    follow: bool,
+
+    /// Print a line of plain text when something changes, instead of
+    /// redrawing the screen, for screen readers. 'w' says where we are
+    #[arg(long, group = "output")]
+    accessible: bool,
+
+    /// Print a JSON object every time anything changes, for scripts
+    #[arg(long, group = "output")]
+    json: bool,
+
+    /// Speak the i3bar protocol, for i3bar and swaybar. Clicks to pause,
+    /// scroll for volume, and right-click to rate
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        group = "output",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_ONELINE
+    )]
+    i3bar: Option<String>,
+
+    /// Print JSON for a waybar custom module
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        group = "output",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_ONELINE
+    )]
+    waybar: Option<String>,
+
+    /// Do one thing and exit, to bind to clicks in a status bar. Takes
+    /// the playback, volume, option and rating actions of --bind
+    #[arg(long, value_enum, group = "output")]
+    click: Option<Click>,
+
     /// Equivalent to '--format title'
     #[arg(short, long)]
     title: bool,
@@ -1,43 +1,150 @@
+mod browse;
 mod display;
+mod find;
 mod input;
+mod keymap;
+mod raw;
+mod resize;
+mod text;
+mod view;
 
-use crate::common::MusicOpts;
+use crate::common::{Action, MusicOpts, Output};
 
 use display::Display;
 use input::KeyHandler;
+use keymap::{Category, Keymap};
+use resize::ResizeHandler;
+use view::SharedView;
 
+use std::sync::Arc;
 use std::thread;
 
-use mpd::Client;
+use mpd::{message::Channel, Client};
 use uuid::Uuid;
 
 pub struct Player;
 
 impl Player {
     pub fn init(address: &str, format: Vec<String>, options: MusicOpts) {
+        // the same keys for the input thread and the help screen
+        let mut keymap = Keymap::new(&options.binds);
+        // lists can't be read out a line at a time, so screen readers don't
+        // get the views made of them
+        if options.output == Output::Accessible {
+            keymap.retain(|a| {
+                !matches!(
+                    Category::of(a),
+                    Category::Browsing
+                        | Category::Playlists
+                        | Category::Finding
+                )
+            });
+        }
+        let keymap = Arc::new(keymap);
+
+        // other programs read our output, so there's no ui or keyboard
+        let interactive =
+            matches!(options.output, Output::Full | Output::Accessible);
+        if !interactive {
+            let Ok(client) = Client::connect(address) else {
+                return Self::error(address);
+            };
+
+            // i3bar tells us about clicks on stdin
+            if let Output::I3bar { .. } = options.output {
+                if let Ok(input_client) = Client::connect(address) {
+                    let input = KeyHandler::new(
+                        input_client,
+                        address,
+                        Uuid::nil(),
+                        SharedView::default(),
+                        Arc::clone(&keymap),
+                    );
+                    thread::spawn(move || input.read_clicks());
+                }
+            }
+
+            let view = SharedView::default();
+            Display::new(
+                client,
+                address,
+                format,
+                Uuid::nil(),
+                view,
+                keymap,
+                options,
+            )
+            .stream();
+            return;
+        }
+
+        let redraws = options.output == Output::Full;
+
         // generate UUID for proper quit handling
         let uuid = Uuid::new_v4();
 
+        // what the display is showing, which input can change
+        let view = SharedView::default();
+
         // initialize display
         let Ok(display_client) = Client::connect(address) else {
             return Self::error(address);
         };
-        let mut display = Display::new(display_client, format, uuid, options);
+        let mut display = Display::new(
+            display_client,
+            address,
+            format,
+            uuid,
+            SharedView::clone(&view),
+            Arc::clone(&keymap),
+            options,
+        );
         let display = thread::spawn(move || display.init());
 
         // initialize input
         let Ok(input_client) = Client::connect(address) else {
             return Self::error(address);
         };
-        let input = KeyHandler::new(input_client, uuid);
+        let input = KeyHandler::new(input_client, address, uuid, view, keymap);
         let input = thread::spawn(move || input.init());
 
+        // initialize resize handler, which never needs to be joined. lines
+        // of text don't care how big the terminal is.
+        if redraws {
+            let resize = ResizeHandler::new(address, uuid);
+            thread::spawn(move || resize.init());
+        }
+
         // join threads and check for panics
         let _ = display.join();
         let _ = input.join();
     }
 
+    // do one thing and exit, for status bars that run a command on click
+    pub fn click(address: &str, action: Action) {
+        let Ok(client) = Client::connect(address) else {
+            return Self::error(address);
+        };
+        let keymap = Arc::new(Keymap::new(&[]));
+        KeyHandler::new(
+            client,
+            address,
+            Uuid::nil(),
+            SharedView::default(),
+            keymap,
+        )
+        .click(action);
+    }
+
     fn error(address: &str) {
         println!("mpc-display-rs: can't connect to server: {address}");
     }
 }
+
+// toggle temp channel to force idle break, so the display redraws
+pub fn refresh(conn: &mut Client) {
+    let _ =
+        conn.subscribe(Channel::new("tmp").expect("can't make temp channel"));
+    let _ =
+        conn.unsubscribe(Channel::new("tmp").expect("can't make temp channel"));
+}
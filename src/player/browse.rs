@@ -0,0 +1,238 @@
+// lists of things to pick from and put in the queue, like the library.
+// input fills these in using its own client, and the display draws them.
+
+pub mod files;
+pub mod library;
+pub mod playlists;
+pub mod search;
+
+use mpd::{Client, Query, Song, Term};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddMode {
+    Append,
+    Insert,
+    Replace,
+}
+
+// something in a list
+#[derive(Debug, Clone)]
+pub enum Item {
+    // a tag value, like an artist, with the tags that lead to it
+    Tag {
+        tag: &'static str,
+        value: String,
+        filters: Vec<(&'static str, String)>,
+    },
+    // a path in the music directory
+    Directory(String),
+    // a stored playlist, or a playlist file
+    Playlist(String),
+    Song(Song),
+}
+
+// one list, and where we are in it
+#[derive(Debug, Default)]
+pub struct Level {
+    pub title: String,
+    pub items: Vec<Item>,
+    pub cursor: usize,
+}
+
+// the lists we've gone into, so we can come back out of them
+#[derive(Debug, Default)]
+pub struct Browser {
+    levels: Vec<Level>,
+    // what happened last, shown at the bottom
+    pub message: Option<String>,
+}
+
+impl Browser {
+    pub const fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    pub fn level(&self) -> Option<&Level> {
+        self.levels.last()
+    }
+
+    pub fn path(&self) -> Vec<&str> {
+        self.levels.iter().map(|l| l.title.as_str()).collect()
+    }
+
+    pub fn selected(&self) -> Option<&Item> {
+        self.level().and_then(|l| l.items.get(l.cursor))
+    }
+
+    // what the current level was opened from
+    pub fn parent(&self) -> Option<&Item> {
+        self.at(self.levels.len().checked_sub(2)?)
+    }
+
+    // what the cursor is on, `depth` levels down
+    pub fn at(&self, depth: usize) -> Option<&Item> {
+        let level = self.levels.get(depth)?;
+        level.items.get(level.cursor)
+    }
+
+    // put the cursor on the first item that `is` picks out, if there is one
+    pub fn select(&mut self, depth: usize, is: impl Fn(&Item) -> bool) {
+        if let Some(level) = self.levels.get_mut(depth) {
+            if let Some(i) = level.items.iter().position(is) {
+                level.cursor = i;
+            }
+        }
+    }
+
+    // new items for a level that's already open, keeping the cursor on them
+    pub fn refill(&mut self, depth: usize, items: Vec<Item>) {
+        if let Some(level) = self.levels.get_mut(depth) {
+            level.cursor = level.cursor.min(items.len().saturating_sub(1));
+            level.items = items;
+        }
+    }
+
+    pub fn push(&mut self, level: Level) {
+        self.levels.push(level);
+        self.message = None;
+    }
+
+    // go back up a level, unless we're at the top
+    pub fn pop(&mut self) -> bool {
+        self.message = None;
+        if self.levels.len() > 1 {
+            self.levels.pop();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn move_by(&mut self, lines: isize) {
+        if let Some(level) = self.levels.last_mut() {
+            let last = level.items.len().saturating_sub(1);
+            level.cursor = level.cursor.saturating_add_signed(lines).min(last);
+        }
+    }
+}
+
+// what's under an item, if anything
+pub fn open(
+    conn: &mut Client,
+    address: &str,
+    item: &Item,
+) -> Result<Option<Level>, String> {
+    match item {
+        Item::Tag { .. } => library::open(conn, item),
+        Item::Directory(path) => files::list(address, path).map(Some),
+        Item::Playlist(name) => {
+            let songs = conn.playlist(name).map_err(|e| e.to_string())?;
+            Ok(Some(Level {
+                title: name.clone(),
+                items: songs.into_iter().map(Item::Song).collect(),
+                cursor: 0,
+            }))
+        }
+        Item::Song(_) => Ok(None),
+    }
+}
+
+// put items in the queue, and say how many songs that was
+pub fn add(
+    conn: &mut Client,
+    items: &[Item],
+    mode: AddMode,
+) -> Result<usize, String> {
+    let err = |e: mpd::error::Error| e.to_string();
+
+    // the server can load whole playlists itself, at the end of the queue
+    if mode != AddMode::Insert
+        && items.iter().all(|i| matches!(i, Item::Playlist(_)))
+    {
+        if mode == AddMode::Replace {
+            conn.clear().map_err(err)?;
+        }
+        let before = conn.status().map_err(err)?.queue_len;
+        for item in items {
+            if let Item::Playlist(name) = item {
+                conn.load(name, 0..).map_err(err)?;
+            }
+        }
+        let added =
+            conn.status().map_err(err)?.queue_len.saturating_sub(before);
+        if mode == AddMode::Replace && added > 0 {
+            conn.switch(0).map_err(err)?;
+        }
+        return Ok(added as usize);
+    }
+
+    let mut songs = Vec::new();
+    for item in items {
+        songs.append(&mut self::songs(conn, item)?);
+    }
+    match mode {
+        AddMode::Append => {
+            for song in &songs {
+                conn.push(song).map_err(err)?;
+            }
+        }
+        AddMode::Insert => {
+            // right after the current song, or at the end without one
+            let status = conn.status().map_err(err)?;
+            let pos = status.song.map_or(status.queue_len, |p| p.pos + 1);
+            for (i, song) in songs.iter().enumerate() {
+                conn.insert(song, pos as usize + i).map_err(err)?;
+            }
+        }
+        AddMode::Replace => {
+            conn.clear().map_err(err)?;
+            for song in &songs {
+                conn.push(song).map_err(err)?;
+            }
+            if !songs.is_empty() {
+                conn.switch(0).map_err(err)?;
+            }
+        }
+    }
+    Ok(songs.len())
+}
+
+// every song an item stands for, in the order they'd be played
+fn songs(conn: &mut Client, item: &Item) -> Result<Vec<Song>, String> {
+    match item {
+        Item::Tag { filters, .. } => {
+            let mut songs = conn
+                .find(&query(filters), None)
+                .map_err(|e| e.to_string())?;
+            library::sort(&mut songs);
+            Ok(songs)
+        }
+        // everything under it, however deep
+        Item::Directory(path) => {
+            let mut query = Query::new();
+            query.and(Term::Base, path.as_str());
+            let mut songs =
+                conn.find(&query, None).map_err(|e| e.to_string())?;
+            songs.sort_by(|a, b| a.file.cmp(&b.file));
+            Ok(songs)
+        }
+        Item::Playlist(name) => conn.playlist(name).map_err(|e| e.to_string()),
+        Item::Song(song) => Ok(vec![song.clone()]),
+    }
+}
+
+fn query<'a>(filters: &'a [(&'static str, String)]) -> Query<'a> {
+    let mut query = Query::new();
+    for (tag, value) in filters {
+        query.and(Term::Tag((*tag).into()), value.as_str());
+    }
+    query
+}
+
+// the first value of a tag, ignoring case like the server does
+pub fn tag<'a>(song: &'a Song, name: &str) -> Option<&'a str> {
+    song.tags
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
@@ -0,0 +1,84 @@
+// the music directory as the server sees it: folders, songs and playlist
+// files. this is read with lsinfo on a raw connection, since the mpd crate
+// only keeps the songs.
+
+use super::{Item, Level};
+use crate::player::raw::{self, RawClient};
+
+pub fn list(address: &str, path: &str) -> Result<Level, String> {
+    let mut conn = RawClient::connect(address)?;
+    lsinfo(&mut conn, path)
+}
+
+// every level from the top down to a song, pointing the way down
+pub fn locate(address: &str, file: &str) -> Result<Vec<Level>, String> {
+    if file.is_empty() {
+        return Err("nothing is playing".into());
+    }
+    if file.contains("://") {
+        return Err("the song isn't in the music directory".into());
+    }
+
+    let mut conn = RawClient::connect(address)?;
+    let mut levels = vec![lsinfo(&mut conn, "")?];
+    let mut path = String::new();
+    for part in file.split('/') {
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(part);
+
+        if let Some(level) = levels.last_mut() {
+            level.cursor = level
+                .items
+                .iter()
+                .position(|item| match item {
+                    Item::Directory(dir) => *dir == path,
+                    Item::Song(song) => song.file == path,
+                    _ => false,
+                })
+                .unwrap_or_default();
+        }
+        if path != file {
+            levels.push(lsinfo(&mut conn, &path)?);
+        }
+    }
+    Ok(levels)
+}
+
+// the last part of a path
+pub fn name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn lsinfo(conn: &mut RawClient, path: &str) -> Result<Level, String> {
+    let pairs = conn.command("lsinfo", &[path])?;
+
+    // each entry starts with what it is, and a song's tags follow it
+    let mut entries: Vec<Vec<(String, String)>> = Vec::new();
+    for (key, value) in pairs {
+        let starts = matches!(key.as_str(), "directory" | "playlist" | "file");
+        match entries.last_mut() {
+            Some(entry) if !starts => entry.push((key, value)),
+            _ => entries.push(vec![(key, value)]),
+        }
+    }
+    let items = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let (key, value) = entry.first()?.clone();
+            match key.as_str() {
+                "directory" => Some(Item::Directory(value)),
+                "playlist" => Some(Item::Playlist(value)),
+                "file" => raw::songs(entry).pop().map(Item::Song),
+                _ => None,
+            }
+        })
+        .collect();
+
+    Ok(Level {
+        title: if path.is_empty() { "Files" } else { name(path) }.into(),
+        items,
+        cursor: 0,
+    })
+}
@@ -0,0 +1,99 @@
+// album artists, then their albums, then the tracks on an album
+
+use super::{query, tag, Item, Level};
+
+use mpd::{Client, Query, Song, Term};
+
+const ARTIST: &str = "AlbumArtist";
+const ALBUM: &str = "Album";
+// shown for songs without the tag
+const UNKNOWN: &str = "(unknown)";
+
+pub fn artists(conn: &mut Client) -> Result<Level, String> {
+    let artists = conn
+        .list(&Term::Tag(ARTIST.into()), &Query::new())
+        .map_err(|e| e.to_string())?;
+    Ok(Level {
+        title: "Library".into(),
+        items: tags(ARTIST, artists, &[]),
+        cursor: 0,
+    })
+}
+
+pub fn open(conn: &mut Client, item: &Item) -> Result<Option<Level>, String> {
+    let Item::Tag {
+        tag,
+        value,
+        filters,
+    } = item
+    else {
+        return Ok(None);
+    };
+    let err = |e: mpd::error::Error| e.to_string();
+
+    let items = match *tag {
+        ARTIST => {
+            let albums = conn
+                .list(&Term::Tag(ALBUM.into()), &query(filters))
+                .map_err(err)?;
+            tags(ALBUM, albums, filters)
+        }
+        ALBUM => {
+            let mut songs = conn.find(&query(filters), None).map_err(err)?;
+            sort(&mut songs);
+            songs.into_iter().map(Item::Song).collect()
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(Level {
+        title: value.clone(),
+        items,
+        cursor: 0,
+    }))
+}
+
+// by album, then disc and track number
+pub fn sort(songs: &mut [Song]) {
+    let number = |song: &Song, name| {
+        tag(song, name)
+            .and_then(|t| t.split('/').next()?.trim().parse::<u32>().ok())
+            .unwrap_or_default()
+    };
+    songs.sort_by_cached_key(|song| {
+        (
+            tag(song, "date").unwrap_or_default().to_string(),
+            tag(song, ALBUM).unwrap_or_default().to_string(),
+            number(song, "disc"),
+            number(song, "track"),
+        )
+    });
+}
+
+// items for tag values, each one narrowing down `filters` a bit more. songs
+// without the tag get an item of their own, since the server takes an empty
+// value to mean the tag is missing.
+fn tags(
+    tag: &'static str,
+    values: Vec<String>,
+    filters: &[(&'static str, String)],
+) -> Vec<Item> {
+    let missing = values.iter().any(String::is_empty);
+    values
+        .into_iter()
+        .filter(|v| !v.is_empty())
+        .chain(missing.then(String::new))
+        .map(|value| {
+            let mut filters = filters.to_vec();
+            filters.push((tag, value.clone()));
+            Item::Tag {
+                tag,
+                value: if value.is_empty() {
+                    UNKNOWN.into()
+                } else {
+                    value
+                },
+                filters,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,237 @@
+// the server's stored playlists, what's in them, and the questions we ask
+// before changing them
+
+use super::{Browser, Item, Level};
+use crate::player::refresh;
+use crate::player::view::SharedView;
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use mpd::Client;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prompt {
+    // a name to save the queue as
+    Save,
+    // a new name for a playlist
+    Rename(String),
+    // whether to really delete a playlist
+    Delete(String),
+}
+
+#[derive(Debug, Default)]
+pub struct Playlists {
+    pub browser: Browser,
+    // how many songs each one has, and how long it is. these take a request
+    // per playlist, so they're filled in later, and only made again when
+    // the server says a playlist has been modified.
+    pub summaries: HashMap<String, (usize, Duration)>,
+    summarized: HashMap<String, String>,
+    // when each playlist was last modified
+    modified: HashMap<String, String>,
+    // what we're asking, and what's been typed in answer
+    pub prompt: Option<Prompt>,
+    pub typed: String,
+    // a playlist to put the cursor on once it shows up, like one that was
+    // just renamed
+    pub follow: Option<String>,
+}
+
+// everything there is to show, fetched without holding on to the view
+pub struct Fetched {
+    level: Level,
+    modified: HashMap<String, String>,
+    // the songs in the playlist that was open, if it still exists
+    open: Option<(String, Vec<Item>)>,
+}
+
+impl Playlists {
+    // the name of the playlist we're in, if we're in one
+    pub fn open(&self) -> Option<&str> {
+        match self.browser.parent() {
+            Some(Item::Playlist(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn ask(&mut self, prompt: Prompt) {
+        self.typed = match &prompt {
+            Prompt::Rename(name) => name.clone(),
+            Prompt::Save | Prompt::Delete(_) => String::new(),
+        };
+        self.prompt = Some(prompt);
+        self.browser.message = None;
+    }
+
+    // put in what was fetched, staying where we were. the list is sorted by
+    // name, so the cursor goes by name too.
+    pub fn update(&mut self, fetched: Fetched) {
+        let selected = match self.browser.at(0) {
+            Some(Item::Playlist(name)) => Some(name.clone()),
+            _ => None,
+        };
+        if self.browser.is_empty() {
+            self.browser.push(fetched.level);
+        } else {
+            self.browser.refill(0, fetched.level.items);
+        }
+        self.modified = fetched.modified;
+
+        let name = match self.follow.take() {
+            Some(name) if self.modified.contains_key(&name) => Some(name),
+            follow => {
+                self.follow = follow;
+                selected
+            }
+        };
+        if let Some(name) = name {
+            self.browser
+                .select(0, |i| matches!(i, Item::Playlist(n) if *n == name));
+        }
+        self.summaries
+            .retain(|name, _| self.modified.contains_key(name));
+
+        if let Some((name, items)) = fetched.open {
+            if self.open() == Some(name.as_str()) {
+                self.browser.refill(1, items);
+            }
+        }
+        // the one that's open might have been renamed or deleted
+        if self
+            .open()
+            .is_some_and(|name| !self.modified.contains_key(name))
+        {
+            self.browser.pop();
+        }
+    }
+
+    // playlists that have changed since they were summarized
+    fn stale(&self) -> Vec<(String, String)> {
+        self.modified
+            .iter()
+            .filter(|(name, modified)| {
+                self.summarized.get(*name) != Some(modified)
+            })
+            .map(|(name, modified)| (name.clone(), modified.clone()))
+            .collect()
+    }
+}
+
+// the names, and the songs in the one that's open. the rest is summarized
+// later, by summarize_later.
+pub fn fetch(conn: &mut Client, open: Option<&str>) -> Result<Fetched, String> {
+    let err = |e: mpd::error::Error| e.to_string();
+    let mut found = conn.playlists().map_err(err)?;
+    found.sort_by_key(|p| p.name.to_lowercase());
+
+    let songs = match open {
+        Some(name) if found.iter().any(|p| p.name == name) => {
+            let songs = conn.playlist(name).map_err(err)?;
+            Some((
+                name.to_string(),
+                songs.into_iter().map(Item::Song).collect(),
+            ))
+        }
+        _ => None,
+    };
+
+    Ok(Fetched {
+        level: Level {
+            title: "Playlists".into(),
+            items: found
+                .iter()
+                .map(|p| Item::Playlist(p.name.clone()))
+                .collect(),
+            cursor: 0,
+        },
+        modified: found.into_iter().map(|p| (p.name, p.last_mod)).collect(),
+        open: songs,
+    })
+}
+
+// count what's in the playlists that have changed, on a connection of its
+// own, so neither keys nor drawing wait on it
+pub fn summarize_later(view: SharedView, address: String) {
+    thread::spawn(move || {
+        let stale = view.lock().expect("can't lock view").playlists.stale();
+        if stale.is_empty() {
+            return;
+        }
+        let Ok(mut conn) = Client::connect(&address) else {
+            return;
+        };
+        let mut summaries = Vec::new();
+        for (name, modified) in stale {
+            let Ok(songs) = conn.playlist(&name) else {
+                continue;
+            };
+            let time = songs.iter().filter_map(|s| s.duration).sum();
+            summaries.push((name, modified, (songs.len(), time)));
+        }
+
+        let mut view = view.lock().expect("can't lock view");
+        let lists = &mut view.playlists;
+        for (name, modified, summary) in summaries {
+            // it might have gone while we were counting
+            if lists.modified.contains_key(&name) {
+                lists.summaries.insert(name.clone(), summary);
+                lists.summarized.insert(name, modified);
+            }
+        }
+        drop(view);
+
+        refresh(&mut conn);
+        let _ = conn.close();
+    });
+}
+
+// do what the prompt was for, and say how it went
+pub fn answer(
+    conn: &mut Client,
+    prompt: &Prompt,
+    typed: &str,
+) -> Result<String, String> {
+    let err = |e: mpd::error::Error| e.to_string();
+    let typed = typed.trim();
+    if typed.is_empty() && !matches!(prompt, Prompt::Delete(_)) {
+        return Err("a playlist needs a name".into());
+    }
+    match prompt {
+        Prompt::Save => {
+            conn.save(typed).map_err(err)?;
+            Ok(format!("saved the queue as {typed}"))
+        }
+        Prompt::Rename(name) => {
+            conn.pl_rename(name, typed).map_err(err)?;
+            Ok(format!("renamed {name} to {typed}"))
+        }
+        Prompt::Delete(name) => {
+            conn.pl_remove(name).map_err(err)?;
+            Ok(format!("deleted {name}"))
+        }
+    }
+}
+
+// take a song out of a playlist
+pub fn remove(conn: &mut Client, name: &str, pos: usize) -> Result<(), String> {
+    let pos = pos.try_into().map_err(|_| "that's too far down")?;
+    conn.pl_delete(name, pos).map_err(|e| e.to_string())
+}
+
+// move a song up (or down, if positive) in a playlist
+pub fn shift(
+    conn: &mut Client,
+    name: &str,
+    from: usize,
+    by: isize,
+) -> Result<(), String> {
+    let to = from
+        .checked_add_signed(by)
+        .ok_or("that's already the top")?;
+    let (Ok(from), Ok(to)) = (from.try_into(), to.try_into()) else {
+        return Err("that's too far down".into());
+    };
+    conn.pl_shift(name, from, to).map_err(|e| e.to_string())
+}
@@ -0,0 +1,221 @@
+// the whole library, searched as you type. plain words are looked for in
+// every tag, "tag:words" in just one, and anything in parentheses goes to
+// the server as a filter expression, like "(artist == 'X' AND date >= '1990')"
+
+use super::{Browser, Item, Level};
+use crate::player::raw::{self, RawClient};
+use crate::player::refresh;
+use crate::player::view::SharedView;
+
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mpd::Client;
+
+// how long typing has to stop before we ask the server
+pub const DEBOUNCE: Duration = Duration::from_millis(250);
+
+// tags that can go before a ':' to search only them
+const TAGS: [&str; 11] = [
+    "artist",
+    "albumartist",
+    "album",
+    "title",
+    "track",
+    "genre",
+    "date",
+    "composer",
+    "performer",
+    "comment",
+    "file",
+];
+
+// what the search thread gets asked to do, for a generation of the query
+pub enum Job {
+    // search once typing has stopped for a moment, if it hasn't changed
+    Later(u64, Instant),
+    // search right away, and say when the results are in
+    Now(u64, Sender<()>),
+}
+
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    // whether keys go to the prompt, or to the results under it
+    pub typing: bool,
+    // what the server didn't like about the query
+    pub error: Option<String>,
+    pub results: Browser,
+    // bumped whenever the query changes, so only the latest one is run
+    pub generation: u64,
+}
+
+impl Search {
+    // the results of a search, or its error over the last good results
+    pub fn show(&mut self, result: Result<Option<Level>, String>) {
+        match result {
+            Ok(level) => {
+                self.results = Browser::default();
+                if let Some(level) = level {
+                    self.results.push(level);
+                }
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+// every search goes through one thread, on a connection kept between them,
+// so keys keep coming in while the server looks
+pub fn worker(
+    view: SharedView,
+    address: String,
+    client: Arc<Mutex<Client>>,
+) -> Sender<Job> {
+    let (jobs, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut conn = None;
+        for job in rx {
+            let generation = match &job {
+                Job::Later(generation, _) | Job::Now(generation, _) => {
+                    *generation
+                }
+            };
+            let current = || {
+                let state = view.lock().expect("can't lock view");
+                (state.search.generation == generation)
+                    .then(|| state.search.query.clone())
+            };
+            // keys typed since then have asked again
+            if let Job::Later(_, asked) = job {
+                if current().is_none() {
+                    continue;
+                }
+                thread::sleep(DEBOUNCE.saturating_sub(asked.elapsed()));
+            }
+            let Some(query) = current() else {
+                continue;
+            };
+            let result = run(&mut conn, &address, &query);
+
+            let mut state = view.lock().expect("can't lock view");
+            if state.search.generation != generation {
+                continue;
+            }
+            state.search.show(result);
+            match job {
+                // a bad query stays at the prompt
+                Job::Now(_, done) => {
+                    state.search.typing = state.search.error.is_some();
+                    drop(state);
+                    let _ = done.send(());
+                }
+                Job::Later(..) => {
+                    drop(state);
+                    refresh(&mut client.lock().expect("can't lock client"));
+                }
+            }
+        }
+    });
+    jobs
+}
+
+// nothing, if there's nothing to look for yet
+fn run(
+    conn: &mut Option<RawClient>,
+    address: &str,
+    query: &str,
+) -> Result<Option<Level>, String> {
+    let Some(args) = args(query) else {
+        return Ok(None);
+    };
+    let songs = raw::songs(search(conn, address, &args)?);
+    Ok(Some(Level {
+        title: query.trim().into(),
+        items: songs.into_iter().map(Item::Song).collect(),
+        cursor: 0,
+    }))
+}
+
+// the server closes connections that sit idle, which only shows when one
+// gets used, so a failure on an old connection gets another go on a new one
+fn search(
+    conn: &mut Option<RawClient>,
+    address: &str,
+    args: &[&str],
+) -> Result<Vec<(String, String)>, String> {
+    if let Some(pairs) =
+        conn.as_mut().and_then(|c| c.command("search", args).ok())
+    {
+        return Ok(pairs);
+    }
+    *conn = None;
+    let mut new = RawClient::connect(address)?;
+    let pairs = new.command("search", args)?;
+    *conn = Some(new);
+    Ok(pairs)
+}
+
+fn args(query: &str) -> Option<Vec<&str>> {
+    let query = query.trim();
+    if query.starts_with('(') {
+        return Some(vec![query]);
+    }
+    let (tag, words) = query
+        .split_once(':')
+        .filter(|(tag, _)| TAGS.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        .unwrap_or(("any", query));
+    let words = words.trim();
+    (!words.is_empty()).then(|| vec![tag, words])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_words_look_everywhere() {
+        assert_eq!(args("kid a"), Some(vec!["any", "kid a"]));
+        assert_eq!(args("  blue  "), Some(vec!["any", "blue"]));
+    }
+
+    #[test]
+    fn nothing_to_look_for() {
+        assert_eq!(args(""), None);
+        assert_eq!(args("   "), None);
+        assert_eq!(args("artist:"), None);
+        assert_eq!(args("artist:  "), None);
+    }
+
+    #[test]
+    fn tags_narrow_it_down() {
+        assert_eq!(args("artist:joni"), Some(vec!["artist", "joni"]));
+        assert_eq!(args("album: kid a"), Some(vec!["album", "kid a"]));
+        assert_eq!(args("Title:Blue"), Some(vec!["Title", "Blue"]));
+    }
+
+    #[test]
+    fn only_known_tags() {
+        assert_eq!(args("mood:sad"), Some(vec!["any", "mood:sad"]));
+        assert_eq!(
+            args("re: your brains"),
+            Some(vec!["any", "re: your brains"])
+        );
+        // only the first colon splits
+        assert_eq!(args("title:a:b"), Some(vec!["title", "a:b"]));
+    }
+
+    #[test]
+    fn expressions_go_as_they_are() {
+        assert_eq!(
+            args("(artist == 'X' AND date >= '1990')"),
+            Some(vec!["(artist == 'X' AND date >= '1990')"])
+        );
+        assert_eq!(args("  (title == 'A')  "), Some(vec!["(title == 'A')"]));
+        // the server says what's wrong with it, not us
+        assert_eq!(args("("), Some(vec!["("]));
+    }
+}
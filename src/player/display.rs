@@ -1,18 +1,45 @@
 use crate::common;
-use crate::common::{ExitCode, MusicOpts};
-
-use std::borrow::Cow::Borrowed;
+use crate::common::{ArtMode, ExitCode, Layout, MusicOpts, Output};
+
+use super::keymap::Keymap;
+use super::text;
+use super::view::{SharedView, View};
+
+mod accessible;
+mod art;
+mod bar;
+mod browse;
+mod eta;
+mod find;
+mod group;
+mod help;
+mod hooks;
+mod json;
+mod layout;
+mod lyrics;
+mod marquee;
+mod sticker;
+mod table;
+mod template;
+
+use art::Art;
+use lyrics::Lyrics;
+use sticker::Stickers;
+
+use std::borrow::Cow::{Borrowed, Owned};
 use std::cmp::min;
+use std::env;
 use std::fmt;
 use std::io;
 use std::io::Write;
-use std::sync::{mpsc, Mutex};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use mpd::{
-    message::Channel, search::Window, song::QueuePlace, Client, Idle, Query,
-    Song, State, Subsystem, Term,
+    message::Channel, search::Window, song::QueuePlace, status::AudioFormat,
+    Client, Idle, Query, Song, State, Status, Subsystem, Term,
 };
 use terminal_size::terminal_size;
 use uuid::Uuid;
@@ -24,18 +51,25 @@ use debug_print::{
 };
 
 const UNKNOWN: &str = "?";
+#[allow(clippy::literal_string_with_formatting_args)]
+// allow because this is a template, not a format string
+const AUDIO: &str = "{bits} bit, {rate} kHz, {channels}, {bitrate} kbps";
+#[allow(clippy::literal_string_with_formatting_args)]
+// allow because this is a template, not a format string
+const TOOLTIP: &str =
+    "{title}\n{artist}\n{album} ({date})\n{elapsed}/{duration}";
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 enum Signal {
     #[default]
     Normal,
-    Help,
     Quit,
 }
 
 #[derive(Debug, Default)]
 pub struct Display {
     client: Mutex<Client>,
+    address: String,
     data: MusicData,
     signal: Signal,
     exit: ExitCode,
@@ -61,27 +95,56 @@ struct MusicData {
     album_track: Option<u32>,
     album_total: Option<u32>,
     queue_track: Option<QueuePlace>,
+    queue_next: Option<QueuePlace>,
     queue_total: Option<u32>,
     time_curr: Option<Duration>,
+    time_fetched: Option<Instant>,
     time_total: Option<Duration>,
     state: State,
     volume: i8,
     ersc_opts: Vec<bool>,
     crossfade: Option<Duration>,
+    audio: Option<AudioFormat>,
+    bitrate: Option<u32>,
+    error: Option<String>,
     rating: Option<String>,
+    stickers: Arc<Stickers>,
+    // bumped when the queue or its stickers change, so rows made from them
+    // can be kept until then
+    queue_version: u64,
+    art: Option<Art>,
+    art_key: Option<u64>,
+    art_slot: art::Slot,
+    lyrics: Option<Arc<Lyrics>>,
+    // shared with the input thread
+    view: SharedView,
+    keymap: Arc<Keymap>,
 }
 
 impl Display {
     #[must_use]
     pub fn new(
         client: Client,
+        address: &str,
         format: Vec<String>,
         uuid: Uuid,
+        view: SharedView,
+        keymap: Arc<Keymap>,
         options: MusicOpts,
     ) -> Self {
+        // art only goes next to a screen that gets drawn
+        let options = MusicOpts {
+            art: if options.output == Output::Full {
+                art::detect(options.art)
+            } else {
+                ArtMode::None
+            },
+            ..options
+        };
         Self {
             client: Mutex::new(client),
-            data: MusicData::new(format, options),
+            address: address.into(),
+            data: MusicData::new(format, view, keymap, options),
             signal: Signal::default(),
             exit: ExitCode::Unknown,
             uuid,
@@ -94,6 +157,14 @@ impl Display {
         data.update_song(&self.client);
         data.update_playlist(&self.client);
         data.update_sticker(&self.client);
+        data.update_stickers(&self.client);
+        self.update_art();
+
+        // screen readers get lines of text instead of a screen
+        if self.data.options.output == Output::Accessible {
+            self.speak();
+            common::clean_exit(self.exit);
+        }
 
         // these functions set up ansi codes and gracefully exit
         common::start_ansi();
@@ -102,6 +173,55 @@ impl Display {
         common::clean_exit(self.exit);
     }
 
+    // print lines for other programs, instead of drawing the screen
+    pub fn stream(&mut self) {
+        let data = &mut self.data;
+        data.update_status(&self.client);
+        data.update_song(&self.client);
+        data.update_playlist(&self.client);
+        data.update_sticker(&self.client);
+        data.update_stickers(&self.client);
+
+        if let Output::I3bar { .. } = self.data.options.output {
+            println!("{}", json::i3bar_header());
+        }
+
+        loop {
+            let fill = |t: &str| template::fill(t, |f| self.data.field(f));
+            let follow = match &self.data.options.output {
+                Output::Full | Output::Accessible => return,
+                Output::Oneline { template, follow } => {
+                    println!("{}", fill(template));
+                    *follow
+                }
+                Output::Json => {
+                    println!("{}", self.data.to_json());
+                    true
+                }
+                Output::I3bar { template } => {
+                    println!("{}", json::i3bar(&fill(template)));
+                    true
+                }
+                Output::Waybar { template } => {
+                    let tooltip = fill(TOOLTIP);
+                    println!(
+                        "{}",
+                        self.data.to_waybar(&fill(template), &tooltip)
+                    );
+                    true
+                }
+            };
+            if !follow {
+                break;
+            }
+
+            self.idle();
+            if self.signal == Signal::Quit {
+                common::clean_exit(self.exit);
+            }
+        }
+    }
+
     pub fn display(&mut self) {
         dprintln!("[startup]");
         self.data.display();
@@ -110,12 +230,8 @@ impl Display {
         let mut counter_idle = 0;
         loop {
             // check signal status
-            match self.signal {
-                Signal::Normal => {}
-                Signal::Quit => break,
-                Signal::Help => {
-                    Self::helptext();
-                }
+            if self.signal == Signal::Quit {
+                break;
             }
 
             // prepare channel
@@ -127,7 +243,8 @@ impl Display {
                     // assign thread handle to external variable
                     _ = thread::spawn({
                         let data = self.data.clone();
-                        move || Self::delay_thread(&rx, data)
+                        let address = self.address.clone();
+                        move || Self::delay_thread(&rx, data, &address)
                     });
                 }
             }
@@ -149,13 +266,23 @@ impl Display {
         }
     }
 
-    fn delay_thread(rx: &mpsc::Receiver<bool>, mut data: MusicData) {
-        const DELAY: u64 = 1;
+    fn delay_thread(
+        rx: &mpsc::Receiver<bool>,
+        mut data: MusicData,
+        address: &str,
+    ) {
+        // how often to check our clock against the server's
+        const RESYNC: Duration = Duration::from_secs(15);
+
+        let tick = data.options.tick;
+        let mut conn: Option<Client> = None;
+        let mut last_sync = Instant::now();
         #[cfg(debug_assertions)]
         let mut counter_delay = 0;
         loop {
-            // sleep before doing anything
-            thread::sleep(Duration::from_secs(DELAY));
+            // sleep until the next tick, lined up with the song's clock so
+            // the elapsed time never lags behind by most of a second
+            thread::sleep(data.until_tick(tick));
             let signal = rx.try_recv().unwrap_or(false);
 
             // check quit signal, otherwise continue
@@ -164,9 +291,20 @@ impl Display {
                 break;
             }
 
-            // increment time only when we print,
-            // otherwise it can break things.
-            data.increment_time(DELAY);
+            // every so often, correct for drift using a separate client,
+            // since the main one is busy idling
+            if last_sync.elapsed() >= RESYNC {
+                last_sync = Instant::now();
+                if conn.is_none() {
+                    conn = Client::connect(address).ok();
+                }
+                match conn.as_mut().map(Client::status) {
+                    Some(Ok(status)) => data.sync_time(&status),
+                    _ => conn = None,
+                }
+                dprintln!("[duration: resync]");
+            }
+
             #[cfg(debug_assertions)]
             {
                 counter_delay += 1;
@@ -186,6 +324,8 @@ impl Display {
             Subsystem::Queue,
             Subsystem::Subscription,
             Subsystem::Sticker,
+            Subsystem::Playlist,
+            Subsystem::Database,
         ]);
         drop(conn);
 
@@ -198,6 +338,8 @@ impl Display {
         };
 
         dprintln!("[subsystems: {subsystems:?}]");
+        let before = self.data.watch();
+        let queue_changed = subsystems.contains(&Subsystem::Queue);
         for i in subsystems {
             let data = &mut self.data;
             // always update status, delay thread requires it
@@ -206,14 +348,22 @@ impl Display {
                 Subsystem::Player => {
                     data.update_song(&self.client);
                     data.update_sticker(&self.client);
+                    self.update_art();
                 }
                 Subsystem::Queue => {
                     data.update_playlist(&self.client);
                     data.update_song(&self.client);
                     data.update_sticker(&self.client);
+                    self.update_art();
                 }
                 Subsystem::Sticker => {
                     data.update_sticker(&self.client);
+                    data.update_song_stickers(&self.client);
+                }
+                Subsystem::Database => data.update_stickers(&self.client),
+                // stored playlists, not the queue
+                Subsystem::Playlist => {
+                    data.update_playlists(&self.client, &self.address);
                 }
                 Subsystem::Subscription => {
                     // get channel list
@@ -225,15 +375,6 @@ impl Display {
 
                     // change signal based on channel/signal state
                     self.signal = if channels.contains(
-                        &Channel::new(
-                            format!("help_{}", self.uuid.simple()).as_str(),
-                        )
-                        .expect("can't make help channel"),
-                    ) {
-                        Signal::Help
-                    } else if self.signal == Signal::Help {
-                        Signal::Normal
-                    } else if channels.contains(
                         &Channel::new(
                             format!("quit_{}", self.uuid.simple()).as_str(),
                         )
@@ -248,34 +389,61 @@ impl Display {
                 _ => {}
             }
         }
+        self.data.fire_hooks(&before, queue_changed);
+        self.data.update_find();
+        self.take_art();
     }
 
-    fn helptext() {
-        const HELPTEXT: &str = "\x1b[2J
-  \x1b[1mh, ?\x1b[0m ......show help text
-  \x1b[1mspace\x1b[0m .....pause/play
-  \x1b[1mpk, nj\x1b[0m ....prev/next track
-  \x1b[1mH, L\x1b[0m ......seek back/ahead
-  \x1b[1m+0, -9\x1b[0m ....volume up/down
-  \x1b[1mERSC\x1b[0m ......repeat, random, single, consume
-  \x1b[1mF\x1b[0m .........shuffle (reorders queue in-place)
-  \x1b[1m{, }\x1b[0m ......adjust current track rating
-  \x1b[1mM\x1b[0m .........stops playback
-  \x1b[1mx, X\x1b[0m ......crossfade up/down
+    fn update_art(&mut self) {
+        let data = &mut self.data;
+        if data.options.art == ArtMode::None {
+            return;
+        }
 
-  \x1b[1;35m~made by aurora~\x1b[0m\
-\x1b[H";
+        // only go looking when the album changes
+        let key = art::key(&data.song);
+        if data.art_key == Some(key) {
+            return;
+        }
+        data.art_key = Some(key);
+        data.art = None;
+        art::fetch_later(
+            self.address.clone(),
+            data.song.clone(),
+            Arc::clone(&data.art_slot),
+        );
+    }
 
-        println!("{HELPTEXT}");
+    // take art that's come in since, unless the song moved on without it
+    fn take_art(&mut self) {
+        let data = &mut self.data;
+        let fetched = data.art_slot.lock().expect("can't lock art").take();
+        if let Some((key, art)) = fetched {
+            if data.art_key == Some(key) {
+                data.art = art;
+            }
+        }
     }
 }
 
 impl MusicData {
     #[must_use]
-    pub fn new(format: Vec<String>, options: MusicOpts) -> Self {
+    pub fn new(
+        format: Vec<String>,
+        view: SharedView,
+        keymap: Arc<Keymap>,
+        options: MusicOpts,
+    ) -> Self {
+        // start out how we were told to
+        let mut state = view.lock().expect("can't lock view");
+        state.layout = options.layout;
+        state.group = options.group;
+        drop(state);
         Self {
             format,
             options,
+            view,
+            keymap,
             ..Self::default()
         }
     }
@@ -293,17 +461,26 @@ impl MusicData {
 
         // modify data
         self.queue_track = status.song;
+        self.queue_next = status.nextsong;
         self.queue_total = match status.queue_len {
             0 => None,
             s => Some(s),
         };
-        self.time_curr = status.elapsed;
-        self.time_total = status.duration;
+        self.sync_time(&status);
         self.state = status.state;
         self.volume = status.volume;
         self.ersc_opts =
             vec![status.repeat, status.random, status.single, status.consume];
         self.crossfade = status.crossfade;
+        self.audio = status.audio;
+        self.bitrate = status.bitrate;
+        self.error = status.error;
+    }
+
+    fn sync_time(&mut self, status: &Status) {
+        self.time_curr = status.elapsed;
+        self.time_total = status.duration;
+        self.time_fetched = Some(Instant::now());
     }
 
     fn update_song(&mut self, client: &Mutex<Client>) {
@@ -330,6 +507,20 @@ impl MusicData {
 
         let date = Self::get_metadata(&song, "date");
 
+        // only go looking for lyrics when the song changes
+        if song.file != self.song.file {
+            self.lyrics = Lyrics::find(
+                &song,
+                self.options.music_dir.as_deref(),
+                self.options.lyrics_dir.as_deref(),
+            )
+            .map(Arc::new);
+            let mut view = self.view.lock().expect("can't lock view");
+            view.scroll = 0;
+            view.cursor = 0;
+            drop(view);
+        }
+
         // mutate data
         self.song = song;
         self.artist.clone_from(&self.song.artist);
@@ -384,6 +575,7 @@ impl MusicData {
         }
         // always assign queue
         self.queue = queue;
+        self.queue_version += 1;
     }
 
     fn update_sticker(&mut self, client: &Mutex<Client>) {
@@ -396,65 +588,22 @@ impl MusicData {
         self.rating = rating;
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    fn progress_bar(&self, options_width: usize) -> String {
+    fn progress_bar(&self, options_width: usize, width: usize) -> String {
         const PADDING: usize = 3;
 
-        // get terminal width for progress bar
-        let width = match terminal_size() {
-            Some((w, _)) => u32::from(w.0),
-            None => 24,
-        };
-        dprintln!("[terminal: width {width}]");
-
-        let progress_total = width as usize - options_width - PADDING;
-
-        // time_total causes div by zero if unset
-        if let Some(time_total) = self.time_total {
-            // calculate size of bar
-            let progress_full = min(
-                progress_total,
-                progress_total
-                * self.time_curr.unwrap_or_default().as_secs() as usize
-                / time_total.as_secs() as usize
-                // add one to make sure the bar is never empty,
-                // and that it looks full at the end of songs
-                + 1,
-            );
-
-            let progress_empty = progress_total - progress_full;
-
-            // careful logic for edge cases
-            // [===>   ]
-            let (open, full) = match progress_full {
-                0 => panic!("this shouldn't be possible"),
-                1 => ("", 0),
-                2 => ("[", 0),
-                _ => ("[", progress_full - 2),
-            };
-            let (close, empty) = match progress_empty {
-                0 => ("", 0),
-                _ => ("]", progress_empty - 1),
-            };
-
-            // assemble bar
-            let padding = " ".repeat(PADDING);
-            let bar1 = "=".repeat(full);
-            let bar2 = " ".repeat(empty);
+        let progress_total = width.saturating_sub(options_width + PADDING);
+        let ratio = self.progress();
 
-            return format!("{padding}{open}{bar1}>{bar2}{close}");
-        }
-
-        // if we can't get the time, throw up a default
         format!(
-            "{}[{}]",
+            "{}{}",
             " ".repeat(PADDING),
-            // subtract 2 for the brackets
-            " ".repeat(progress_total as usize - 2),
+            bar::render(self.options.bar, progress_total, ratio)
         )
     }
 
-    fn print_header(&self) -> String {
+    #[allow(clippy::literal_string_with_formatting_args)]
+    // allow because this is a template, not a format string
+    fn print_header(&self, width: usize) -> String {
         const COL_ARTIST: &str = "\x1b[1;36m"; // bold cyan
         const COL_TITLE: &str = "\x1b[1;34m"; // bold blue
         const COL_TRACK: &str = "\x1b[32m"; // green
@@ -464,89 +613,71 @@ impl MusicData {
         const COL_PLAY: &str = "\x1b[32m"; // green
         const COL_PAUSE: &str = "\x1b[31m"; // red
         const COL_BAR: &str = "\x1b[35m"; // magenta
+        const COL_NEXT: &str = "\x1b[2m"; // dim
+        const COL_ERROR: &str = "\x1b[1;41m"; // bold, red background
         const COL_END: &str = "\x1b[0m"; // reset
 
-        // start defining some variables
-        let artist = self.artist.clone().unwrap_or_else(|| UNKNOWN.into());
-        let title = Self::read_title(&self.song);
-
-        let album_track = self
-            .album_track
-            .map_or_else(|| UNKNOWN.into(), |s| s.to_string());
-        let album_total = self
-            .album_total
-            .map_or_else(|| UNKNOWN.into(), |s| s.to_string());
-
-        let album = self.album.clone().unwrap_or_else(|| UNKNOWN.into());
-
-        let date = self.date.clone().unwrap_or_else(|| UNKNOWN.into());
-
-        let state = match self.state {
-            State::Play => "|>",
-            State::Pause => "[]",
-            State::Stop => "><",
-        };
-
-        let queue_track = self
-            .queue_track
-            .map_or_else(|| UNKNOWN.into(), |s| (s.pos + 1).to_string());
-        let queue_total = self
-            .queue_total
-            .map_or_else(|| UNKNOWN.into(), |s| s.to_string());
-
-        let elapsed_pretty = Self::get_pretty_time(self.time_curr)
-            .unwrap_or_else(|| UNKNOWN.into());
-        let duration_pretty = Self::get_pretty_time(self.time_total)
-            .unwrap_or_else(|| UNKNOWN.into());
-
-        let percent = match (self.time_curr, self.time_total) {
-            (Some(curr), Some(total)) => {
-                (100 * curr.as_secs() / total.as_secs()).to_string()
-            }
-            _ => UNKNOWN.into(),
-        };
-
-        let rating = self.get_rating();
-
-        let ersc_str = self.get_ersc();
-        let volume = self.volume;
-        let crossfade = self
-            .crossfade
-            .map_or_else(String::new, |t| format!(" ({})", t.as_secs()));
-
         // apply coloring!!!
         let col_state = match self.state {
             State::Play => COL_PLAY,
             State::Pause | State::Stop => COL_PAUSE,
         };
 
-        // get visual progress bar
-        let progress = self.progress_bar(
-            format!("{ersc_str}, {volume: >3}%{crossfade}").len(),
-        );
+        // get visual progress bar, which fills whatever's left of the line
+        let options =
+            template::fill("{ersc}, {volume:>3}%{xfade}", |f| self.field(f));
+        let progress = self.progress_bar(text::width(&options), width);
 
         // final format text
+        let header = format!(
+            "{COL_TITLE}{{title}}{COL_END} * {COL_ARTIST}{{artist}}{COL_END}\n({COL_TRACK}#{{album_track}}/{{album_total}}{COL_END}) {COL_ALBUM}{{album}}{COL_END} {COL_DATE}({{date}}){COL_END}\n{col_state}{{state}} {{queue_track}}/{{queue_total}}: {{elapsed}}/{{duration}}, {{percent}}% ({{queue_left}}/{{queue_time}} left){COL_END}  {COL_RATING}{{rating}}{COL_END}\n"
+        );
+        // optional extra lines
+        let footer = [
+            (self.options.audio, format!("{COL_NEXT}{AUDIO}{COL_END}")),
+            (
+                self.options.next,
+                format!("{COL_NEXT}Up next: {{next}}{COL_END}"),
+            ),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, line)| "\n".to_string() + &line)
+        .collect::<String>();
+
+        // errors go first, so they can't be missed
+        let error = self.error.as_ref().map_or_else(String::new, |_| {
+            format!("{COL_ERROR}error: {{error}} (! to clear){COL_END}\n")
+        });
+
         format!(
-            "{COL_TITLE}{title}{COL_END} * {COL_ARTIST}{artist}{COL_END}\n({COL_TRACK}#{album_track}/{album_total}{COL_END}) {COL_ALBUM}{album}{COL_END} {COL_DATE}({date}){COL_END}\n{col_state}{state} {queue_track}/{queue_total}: {elapsed_pretty}/{duration_pretty}, {percent}%{COL_END}  {COL_RATING}{rating}{COL_END}\n{col_state}{ersc_str}, {volume: >3}%{crossfade}{COL_END}{COL_BAR}{progress}{COL_END}"
+            "{}{}{col_state}{options}{COL_END}{COL_BAR}{progress}{COL_END}{}",
+            template::fill(&error, |f| self.field(f)),
+            template::fill(&header, |f| self.field(f)),
+            template::fill(&footer, |f| self.field(f))
         )
     }
 
     fn get_rating(&self) -> String {
+        self.fmt_rating(self.rating.as_deref())
+    }
+
+    fn fmt_rating(&self, rating: Option<&str>) -> String {
         fn fmt_r(r: &str) -> String {
             format!("rating: {r}")
         }
 
         if self.options.ratings && !self.options.easter {
-            self.rating.clone().map_or_else(
+            rating.map_or_else(
                 || " ? ? ? ? ?".into(),
                 |r| {
                     const STARS: [&str; 3] = ["<3", "< ", " ."];
 
                     match r.parse::<usize>() {
-                        Err(_) => fmt_r(&r),
+                        Err(_) => fmt_r(r),
                         Ok(n) => {
                             if n > 10 {
-                                return fmt_r(&r);
+                                return fmt_r(r);
                             }
                             let (a, b) = (n / 2, n % 2);
                             let c = std::cmp::max(0, 5 - a - b);
@@ -577,9 +708,7 @@ impl MusicData {
             ];
             format!(
                 "\x1b[40m {} \x1b[0m",
-                CHRISTGAU[self
-                    .rating
-                    .clone()
+                CHRISTGAU[rating
                     .unwrap_or_default()
                     .parse::<usize>()
                     .unwrap_or_default()
@@ -590,15 +719,63 @@ impl MusicData {
         }
     }
 
-    #[allow(clippy::let_and_return)]
     fn print_queue(
         &self,
         height: u32,
         width: u32,
         header_height: u32,
     ) -> String {
+        const COL_FOCUS: &str = "\x1b[1;4m"; // bold, underline
+
         // get height of queue
-        let queue_height = height - header_height;
+        let queue_height = height.saturating_sub(header_height);
+        let (view, focus) = {
+            let mut view = self.view.lock().expect("can't lock view");
+            view.page = queue_height as usize;
+            (view.view, view.focus.filter(|f| *f < self.queue.len()))
+        };
+
+        // lyrics take the place of the queue
+        if view == View::Lyrics {
+            let lyrics = self.print_lyrics(
+                queue_height,
+                width.try_into().expect("nothing should be that big"),
+            );
+            return Self::pad_queue(&lyrics, queue_height);
+        }
+
+        if view.is_browser() {
+            let list = self.print_browser(
+                queue_height,
+                width.try_into().expect("nothing should be that big"),
+            );
+            return Self::pad_queue(&list, queue_height);
+        }
+
+        if view == View::Find {
+            let found = self.print_find(
+                queue_height,
+                width.try_into().expect("nothing should be that big"),
+            );
+            return Self::pad_queue(&found, queue_height);
+        }
+
+        // album groups and tables lay themselves out, so they don't need
+        // wrapping
+        if self.options.group {
+            let groups = self.print_groups(
+                queue_height,
+                width.try_into().expect("nothing should be that big"),
+            );
+            return Self::pad_queue(&groups, queue_height);
+        }
+        if self.options.table {
+            let table = self.print_table(
+                queue_height,
+                width.try_into().expect("nothing should be that big"),
+            );
+            return Self::pad_queue(&table, queue_height);
+        }
 
         // get size of queue and current song index
         let queue_size: u32 = self.queue.len().try_into().unwrap_or(0);
@@ -608,6 +785,10 @@ impl MusicData {
         let padding = 1 + queue_size.checked_ilog10().unwrap_or_default();
 
         // queue to vec of song-strings
+        let etas = self.etas(eta::around(
+            focus.unwrap_or(song_pos as usize),
+            queue_height,
+        ));
         let mut counter = 0;
         let queue = self
             .queue
@@ -616,27 +797,47 @@ impl MusicData {
             .map(|i| {
                 counter += 1;
                 let is_curr = counter == song_pos + 1;
-                self.format_song(i, counter, padding, is_curr)
+                let eta =
+                    etas.get(counter as usize - 1).and_then(Option::as_ref);
+                let line = self.format_song(i, counter, padding, is_curr, eta);
+                if focus == Some(counter as usize - 1) {
+                    format!("{COL_FOCUS}{line}\x1b[0m")
+                } else {
+                    line
+                }
             })
             .collect::<Vec<_>>();
 
-        // filter and string-ify the queue
+        // filter and string-ify the queue, around the focus if there is one
+        let centre = focus
+            .and_then(|f| u32::try_from(f).ok())
+            .unwrap_or(song_pos);
         let queue =
-            Self::filter_queue(&queue, queue_height, queue_size, song_pos)
+            Self::filter_queue(&queue, queue_height, queue_size, centre)
                 .join("\n");
 
-        // wrap the queue
-        let opt = textwrap::Options::new(
-            width.try_into().expect("nothing should be that big"),
-        );
-        let queue = textwrap::wrap(&queue, opt);
+        // wrap or truncate the queue
+        let width = width.try_into().expect("nothing should be that big");
+        let queue = if self.options.truncate {
+            queue
+                .lines()
+                .map(|line| Owned(text::truncate(line, width)))
+                .collect()
+        } else {
+            textwrap::wrap(&queue, textwrap::Options::new(width))
+        };
 
         // (again) get size of queue and current song index
         let queue_size: u32 =
             queue.len().try_into().expect("nothing should be that big");
         let mut song_pos: Option<u32> = None;
         for (i, v) in queue.iter().enumerate() {
-            if v.starts_with('>') || v.starts_with('\x1b') {
+            let found = if focus.is_some() {
+                v.starts_with(COL_FOCUS)
+            } else {
+                v.starts_with('>') || v.starts_with('\x1b')
+            };
+            if found {
                 song_pos =
                     Some(i.try_into().expect("nothing should be that big"));
             }
@@ -647,21 +848,24 @@ impl MusicData {
         let queue =
             Self::filter_queue(&queue, queue_height, queue_size, song_pos);
 
+        // string-ify and add padding to queue
+        Self::pad_queue(queue, queue_height)
+    }
+
+    fn pad_queue<T: AsRef<str>>(queue: &[T], queue_height: u32) -> String {
         // create padding to add later
-        let len = queue.len().try_into().unwrap_or(0);
-        let mut diff: i32 = ((queue_height) - len).try_into().unwrap_or(0);
-        if diff < 0 {
-            diff = 0;
-        }
-        diff += 1;
-        let diff = diff.try_into().unwrap_or(0);
-        let queue_padding = vec![""; diff].join("\n");
+        // an empty queue is still one (blank) line
+        let len: u32 = queue.len().try_into().unwrap_or(0);
+        let diff = queue_height.saturating_sub(len.max(1)) as usize;
+        let queue_padding = "\n".repeat(diff);
 
         // string-ify and add padding to queue
-        let queue = queue.join("\n") + &queue_padding;
-
-        // finally return
-        queue
+        let queue = queue
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>()
+            .join("\n");
+        queue + &queue_padding
     }
 
     #[allow(clippy::let_and_return)]
@@ -692,6 +896,7 @@ impl MusicData {
         index: u32,
         padding: u32,
         is_curr: bool,
+        eta: Option<&String>,
     ) -> String {
         // get colors
         const COL_CURR: &str = "\x1b[7m"; // reverse
@@ -714,9 +919,12 @@ impl MusicData {
             {
                 continue;
             }
-            tags.push(
-                Self::get_metadata(song, &v).unwrap_or_else(|| UNKNOWN.into()),
-            );
+            let value = if v == "eta" {
+                eta.cloned()
+            } else {
+                self.song_tag(song, &v)
+            };
+            tags.push(value.unwrap_or_else(|| UNKNOWN.into()));
         }
 
         let songtext = tags.join("  *  ");
@@ -728,14 +936,14 @@ impl MusicData {
     // display size, total queue size, current position in queue
     fn get_centered_index(display: u32, total: u32, curr: u32) -> u32 {
         dprintln!("[get_centered_index()]\n[display: {display}, total: {total}, curr: {curr}]");
-        if total <= display {
+        if total <= display || display == 0 {
             return 0;
         }
 
         let half = (display - 1) / 2;
         #[allow(clippy::cast_possible_wrap)]
         let head = curr as i32 - half as i32;
-        let tail = if display % 2 == 0 {
+        let tail = if display.is_multiple_of(2) {
             curr + half + 1
         } else {
             curr + half
@@ -753,8 +961,32 @@ impl MusicData {
         }
     }
 
-    fn increment_time(&mut self, n: u64) {
-        self.time_curr = self.time_curr.map(|t| t + Duration::from_secs(n));
+    // the server only tells us the elapsed time when we ask, so count
+    // forwards from then while playing. never go past the end of the song,
+    // the server will switch songs (or crossfade) on its own.
+    fn elapsed(&self) -> Option<Duration> {
+        let curr = self.time_curr?;
+        let curr = match (self.state, self.time_fetched) {
+            (State::Play, Some(fetched)) => curr + fetched.elapsed(),
+            _ => curr,
+        };
+        Some(self.time_total.map_or(curr, |total| min(curr, total)))
+    }
+
+    // how far through the song we are, from 0 to 1
+    fn progress(&self) -> Option<f64> {
+        let total = self.time_total?.as_secs_f64();
+        if total == 0.0 {
+            return None;
+        }
+        Some((self.elapsed()?.as_secs_f64() / total).clamp(0.0, 1.0))
+    }
+
+    fn until_tick(&self, tick: Duration) -> Duration {
+        let elapsed = self.elapsed().unwrap_or_default().as_nanos();
+        let tick_nanos = tick.as_nanos().max(1);
+        let remainder = tick_nanos - elapsed % tick_nanos;
+        Duration::from_nanos(remainder.try_into().unwrap_or(u64::MAX))
     }
 
     #[allow(clippy::needless_pass_by_value)]
@@ -781,8 +1013,12 @@ impl MusicData {
 
     fn get_pretty_time(dur: Option<Duration>) -> Option<String> {
         let n = dur?.as_secs();
-        let (min, sec) = (n / 60, n % 60);
-        Some(format!("{min}:{sec:0>2}"))
+        let (hour, min, sec) = (n / 3600, n / 60 % 60, n % 60);
+        if hour > 0 {
+            Some(format!("{hour}:{min:0>2}:{sec:0>2}"))
+        } else {
+            Some(format!("{min}:{sec:0>2}"))
+        }
     }
 
     fn get_ersc(&self) -> String {
@@ -806,6 +1042,7 @@ impl MusicData {
         match tag {
             "title" => Some(Self::read_title(song)),
             "artist" => song.artist.clone(),
+            "time" => Self::get_pretty_time(song.duration),
             _ => {
                 let mut value = None;
                 for (k, v) in &song.tags {
@@ -824,12 +1061,22 @@ impl MusicData {
             if file.is_empty() {
                 UNKNOWN.into()
             } else {
-                file.split('/').last().unwrap_or(UNKNOWN).into()
+                file.split('/').next_back().unwrap_or(UNKNOWN).into()
             }
         })
     }
 }
 
+// where we keep things between runs
+fn cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache"))
+        })
+        .map(|d| d.join("mpc-display-rs"))
+}
+
 impl fmt::Display for MusicData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // get terminal height
@@ -839,22 +1086,80 @@ impl fmt::Display for MusicData {
         };
         dprintln!("[terminal: height {height}, width {width}]");
 
+        // help covers the whole screen
+        if self.view.lock().expect("can't lock view").view == View::Help {
+            let help = self.print_help(
+                height,
+                width.try_into().expect("nothing should be that big"),
+            );
+            return write!(f, "{}", Self::pad_queue(&help, height));
+        }
+
+        let width_usize: usize =
+            width.try_into().expect("nothing should be that big");
+        let layout = self.view.lock().expect("can't lock view").layout;
+        let layout = layout::fit(layout, width_usize, height as usize);
+        dprintln!("[layout: {layout:?}]");
+        match layout {
+            Layout::Queue => {
+                return write!(f, "{}", self.print_queue(height, width, 0));
+            }
+            Layout::Split => {
+                return write!(f, "{}", self.print_split(height, width_usize));
+            }
+            Layout::Big => {
+                return write!(f, "{}", self.print_big(height, width_usize));
+            }
+            Layout::Top | Layout::Bottom | Layout::Header => {}
+        }
+
+        // make room for the art next to the header
+        let header_width = match self.art {
+            Some(_) => width_usize.saturating_sub(art::ART_COLS + 1),
+            None => width_usize,
+        };
+
         // get header size
-        let header = self.print_header();
-        let opt = textwrap::Options::new(
-            width.try_into().expect("nothing should be that big"),
-        );
-        let header = textwrap::fill(header.as_str(), opt);
-        let header_height = (1 + header.matches('\n').count())
-            .try_into()
-            .expect("can't cast header size");
+        let (header, header_height) = self.wrapped_header(header_width);
         dprintln!("[header_height: {header_height}]");
 
-        write!(
-            f,
-            "{}\n{}",
-            header,
-            self.print_queue(height, width, header_height),
-        )
+        // not enough room for a queue, so just the header
+        let layout = if height < header_height + layout::QUEUE_MIN_HEIGHT {
+            Layout::Header
+        } else {
+            layout
+        };
+
+        let art_row = match layout {
+            Layout::Bottom => {
+                let queue_height = height - header_height;
+                write!(
+                    f,
+                    "{}\n{}",
+                    self.print_queue(height, width, header_height),
+                    header,
+                )?;
+                queue_height as usize + 1
+            }
+            Layout::Header => {
+                write!(f, "{header}")?;
+                1
+            }
+            _ => {
+                write!(
+                    f,
+                    "{}\n{}",
+                    header,
+                    self.print_queue(height, width, header_height),
+                )?;
+                1
+            }
+        };
+
+        if let Some(art) = &self.art {
+            let col = header_width + 2;
+            write!(f, "{}", art::render(art, self.options.art, art_row, col))?;
+        }
+        Ok(())
     }
 }
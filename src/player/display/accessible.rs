@@ -0,0 +1,244 @@
+use super::hooks::Watch;
+use super::table::capitalize;
+use super::{Display, MusicData, Signal, UNKNOWN};
+use crate::common::Action;
+use crate::player::view::View;
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use mpd::State;
+
+const OPTIONS: [&str; 4] = ["repeat", "random", "single", "consume"];
+
+impl Display {
+    // a line of plain text for each change, instead of redrawing the screen,
+    // so screen readers only have to read what's new
+    pub(super) fn speak(&mut self) {
+        println!("{}", self.data.where_am_i());
+
+        loop {
+            let before = self.data.watch();
+            self.idle();
+            if self.signal == Signal::Quit {
+                break;
+            }
+
+            for line in self.data.changes(&before) {
+                println!("{line}");
+            }
+            self.answer();
+        }
+    }
+
+    // respond to the keys that want something said
+    fn answer(&self) {
+        let mut view = self.data.view.lock().expect("can't lock view");
+        if view.where_am_i {
+            view.where_am_i = false;
+            drop(view);
+            println!("{}", self.data.where_am_i());
+        } else if view.view == View::Help {
+            // there's nothing to scroll or filter here, so say it all
+            view.toggle(View::Help);
+            drop(view);
+            println!("{}", self.data.spoken_keys());
+        }
+    }
+}
+
+impl MusicData {
+    // short sentences for whatever changed since `before`
+    fn changes(&self, before: &Watch) -> Vec<String> {
+        let after = self.watch();
+        let mut lines = Vec::new();
+
+        if after.song_changed(before) {
+            lines.push(format!("Now {}.", self.song_summary()));
+        }
+        if after.state != before.state {
+            lines.push(format!("{}.", Self::spoken_state(after.state)));
+        }
+        if after.volume != before.volume {
+            lines.push(format!("Volume {}%.", after.volume));
+        }
+        for (i, name) in OPTIONS.iter().enumerate() {
+            let on = after.ersc.get(i);
+            if on != before.ersc.get(i) && on.is_some() {
+                lines.push(format!("{} {}.", capitalize(name), on_off(on)));
+            }
+        }
+        if after.crossfade != before.crossfade {
+            lines.push(format!("Crossfade {}.", self.spoken_crossfade()));
+        }
+        if after.rating_changed(before) {
+            lines.push(format!("{}.", self.spoken_rating()));
+        }
+        if after.error != before.error {
+            lines.push(after.error.as_ref().map_or_else(
+                || "Error cleared.".into(),
+                |e| format!("Error: {e}."),
+            ));
+        }
+        lines
+    }
+
+    // everything at once, for when the user asks
+    pub(super) fn where_am_i(&self) -> String {
+        if self.song.file.is_empty() {
+            return format!(
+                "{}. Nothing playing. {} songs in the queue. Volume {}%.",
+                Self::spoken_state(self.state),
+                self.queue.len(),
+                self.volume,
+            );
+        }
+
+        let mut lines = vec![
+            format!(
+                "{} {}.",
+                Self::spoken_state(self.state),
+                self.song_summary()
+            ),
+            self.spoken_position(),
+            format!("Volume {}%.", self.volume),
+            format!(
+                "{}, crossfade {}.",
+                OPTIONS
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| {
+                        format!("{name} {}", on_off(self.ersc_opts.get(i)))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                self.spoken_crossfade(),
+            ),
+        ];
+        if self.options.ratings {
+            lines.push(format!("{}.", self.spoken_rating()));
+        }
+        lines.push(format!(
+            "Next: {}.",
+            self.next_song().replace(" * ", " by ")
+        ));
+        lines.push(format!(
+            "{} left in the queue.",
+            spoken_time(self.queue_left().unwrap_or_default())
+        ));
+        if let Some(error) = &self.error {
+            lines.push(format!("Error: {error}."));
+        }
+        lines.join(" ")
+    }
+
+    // every key, as "Pause/play: space." with no lining up
+    fn spoken_keys(&self) -> String {
+        let keys = Action::value_variants()
+            .iter()
+            .filter_map(|action| {
+                let help = action.to_possible_value()?.get_help()?.to_string();
+                let keys = self
+                    .keymap
+                    .keys(*action)
+                    .iter()
+                    .map(|(k, _)| k.name())
+                    .collect::<Vec<_>>();
+                (!keys.is_empty())
+                    .then(|| format!("{help}: {}.", keys.join(", ")))
+            })
+            .collect::<Vec<_>>();
+        format!("Keys. {}", keys.join(" "))
+    }
+
+    // "Title by Artist, from Album (Date)"
+    fn song_summary(&self) -> String {
+        let mut summary = format!(
+            "{} by {}",
+            Self::read_title(&self.song),
+            self.artist.as_deref().unwrap_or(UNKNOWN)
+        );
+        if let Some(album) = &self.album {
+            let _ = write!(summary, ", from {album}");
+            if let Some(date) = &self.date {
+                let _ = write!(summary, " ({date})");
+            }
+        }
+        summary
+    }
+
+    fn spoken_position(&self) -> String {
+        let mut position = Vec::new();
+        if let (Some(track), Some(total)) = (self.album_track, self.album_total)
+        {
+            position.push(format!("Track {track} of {total} on the album"));
+        }
+        if let (Some(place), Some(total)) = (self.queue_track, self.queue_total)
+        {
+            position.push(format!("{} of {total} in the queue", place.pos + 1));
+        }
+        let time = match (self.elapsed(), self.time_total) {
+            (Some(elapsed), Some(total)) => {
+                format!("{} of {}", spoken_time(elapsed), spoken_time(total))
+            }
+            (Some(elapsed), None) => spoken_time(elapsed),
+            _ => String::new(),
+        };
+
+        [position.join(", "), time]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s + ".")
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn spoken_rating(&self) -> String {
+        self.rating.as_ref().map_or_else(
+            || "Not rated".into(),
+            |r| format!("Rated {r} out of 10"),
+        )
+    }
+
+    fn spoken_crossfade(&self) -> String {
+        self.crossfade
+            .filter(|t| !t.is_zero())
+            .map_or_else(|| "off".into(), spoken_time)
+    }
+
+    const fn spoken_state(state: State) -> &'static str {
+        match state {
+            State::Play => "Playing",
+            State::Pause => "Paused",
+            State::Stop => "Stopped",
+        }
+    }
+}
+
+fn on_off(flag: Option<&bool>) -> &'static str {
+    if flag.copied().unwrap_or_default() {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+// "1 hour 2 minutes 5 seconds", leaving out anything that's zero
+fn spoken_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    let parts = [
+        (secs / 3600, "hour"),
+        (secs / 60 % 60, "minute"),
+        (secs % 60, "second"),
+    ]
+    .into_iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, unit)| format!("{n} {unit}{}", if n == 1 { "" } else { "s" }))
+    .collect::<Vec<_>>();
+    if parts.is_empty() {
+        "0 seconds".into()
+    } else {
+        parts.join(" ")
+    }
+}
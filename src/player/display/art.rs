@@ -0,0 +1,356 @@
+use super::MusicData;
+use crate::common::ArtMode;
+use crate::player::raw::RawClient;
+use crate::player::refresh;
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{imageops::FilterType, DynamicImage, Rgba, RgbaImage};
+use mpd::{Client, Song};
+
+#[allow(unused_imports)]
+use debug_print::{
+    debug_eprint as deprint, debug_eprintln as deprintln,
+    debug_print as dprint, debug_println as dprintln,
+};
+
+// size of the art next to the header, in terminal cells
+pub const ART_ROWS: usize = 4;
+pub const ART_COLS: usize = 2 * ART_ROWS;
+
+// decoded art gets shrunk to this before we keep it around
+const THUMBNAIL: u32 = 256;
+
+// art fetched in the background, with the key it was fetched for, waiting
+// for the display to pick it up
+pub type Slot = Arc<Mutex<Option<(u64, Option<Art>)>>>;
+
+#[derive(Debug, Clone)]
+pub struct Art {
+    id: u32,
+    image: Arc<RgbaImage>,
+    // whether kitty already has this image, shared with the delay thread
+    sent: Arc<AtomicBool>,
+}
+
+// figure out what the terminal can do, if we weren't told
+pub fn detect(mode: ArtMode) -> ArtMode {
+    if mode != ArtMode::Auto {
+        return mode;
+    }
+
+    let var = |name| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    let program = var("TERM_PROGRAM");
+
+    let mode = if env::var_os("KITTY_WINDOW_ID").is_some()
+        || term.contains("kitty")
+        || term.contains("ghostty")
+        || program == "WezTerm"
+    {
+        ArtMode::Kitty
+    } else if ["foot", "mlterm", "contour", "sixel"]
+        .iter()
+        .any(|t| term.contains(t))
+    {
+        ArtMode::Sixel
+    } else if matches!(var("COLORTERM").as_str(), "truecolor" | "24bit") {
+        ArtMode::Blocks
+    } else {
+        ArtMode::Ascii
+    };
+    dprintln!("[art: detected {mode:?}]");
+    mode
+}
+
+// find the art for a song, from the cache or from the server
+pub fn fetch(address: &str, song: &Song) -> Option<Art> {
+    if song.file.is_empty() {
+        return None;
+    }
+
+    let key = key(song);
+    let path =
+        super::cache_dir().map(|d| d.join("art").join(format!("{key:016x}")));
+
+    let bytes = path.as_ref().and_then(|p| fs::read(p).ok()).or_else(|| {
+        let bytes = download(address, &song.file)?;
+        if let Some(p) = &path {
+            if let Some(dir) = p.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            let _ = fs::write(p, &bytes);
+        }
+        Some(bytes)
+    })?;
+
+    let image = image::load_from_memory(&bytes).ok()?;
+    let image = image.thumbnail(THUMBNAIL, THUMBNAIL).to_rgba8();
+    dprintln!("[art: {}x{}]", image.width(), image.height());
+
+    Some(Art {
+        // kitty needs a non-zero id
+        #[allow(clippy::cast_possible_truncation)]
+        id: (key as u32).max(1),
+        image: Arc::new(image),
+        sent: Arc::default(),
+    })
+}
+
+// fetch on a thread of its own, since a download can take a while, then
+// wake the display so it picks the art up
+pub fn fetch_later(address: String, song: Song, slot: Slot) {
+    thread::spawn(move || {
+        let art = fetch(&address, &song);
+        *slot.lock().expect("can't lock art") = Some((key(&song), art));
+        if let Ok(mut conn) = Client::connect(&address) {
+            refresh(&mut conn);
+            let _ = conn.close();
+        }
+    });
+}
+
+// everything on an album should share art, so key the cache by album
+pub fn key(song: &Song) -> u64 {
+    let artist = MusicData::get_metadata(song, "albumartist")
+        .or_else(|| song.artist.clone())
+        .unwrap_or_default();
+    let album = MusicData::get_metadata(song, "album").unwrap_or_else(|| {
+        // without an album, the best we can do is the directory
+        song.file
+            .rsplit_once('/')
+            .map(|(d, _)| d.into())
+            .unwrap_or_default()
+    });
+
+    // FNV-1a, so the cache stays valid between builds
+    format!("{artist}\0{album}")
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+fn download(address: &str, file: &str) -> Option<Vec<u8>> {
+    let mut conn = RawClient::connect(address).ok()?;
+
+    // try a cover file in the song's directory first, then embedded art
+    ["albumart", "readpicture"].iter().find_map(|cmd| {
+        let mut buf = Vec::new();
+        loop {
+            let offset = buf.len().to_string();
+            let (pairs, mut chunk) = conn.binary(cmd, &[file, &offset]).ok()?;
+            let size: usize = pairs
+                .iter()
+                .find(|(k, _)| k == "size")
+                .and_then(|(_, v)| v.parse().ok())?;
+            if chunk.is_empty() {
+                return None;
+            }
+            buf.append(&mut chunk);
+            if buf.len() >= size {
+                dprintln!("[art: {cmd} gave {size} bytes]");
+                return Some(buf);
+            }
+        }
+    })
+}
+
+// draw the art with its top left corner at (row, col), counting from 1
+pub fn render(art: &Art, mode: ArtMode, row: usize, col: usize) -> String {
+    let goto = |r: usize| format!("\x1b[{};{col}H", row + r);
+    match mode {
+        ArtMode::None | ArtMode::Auto => String::new(),
+        ArtMode::Kitty => goto(0) + &kitty(art),
+        ArtMode::Sixel => goto(0) + &sixel(art),
+        ArtMode::Blocks => blocks(art)
+            .iter()
+            .enumerate()
+            .map(|(r, line)| goto(r) + line)
+            .collect(),
+        ArtMode::Ascii => ascii(art)
+            .iter()
+            .enumerate()
+            .map(|(r, line)| goto(r) + line)
+            .collect(),
+    }
+}
+
+fn kitty(art: &Art) -> String {
+    let mut out = String::new();
+    let id = art.id;
+
+    // send the pixels once, after that we only need to place them
+    if !art.sent.swap(true, Ordering::Relaxed) {
+        let (w, h) = art.image.dimensions();
+        let data = STANDARD.encode(art.image.as_raw());
+        let chunks = data.as_bytes().chunks(4096).collect::<Vec<_>>();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(i + 1 < chunks.len());
+            let chunk = String::from_utf8_lossy(chunk);
+            if i == 0 {
+                let _ = write!(
+                    out,
+                    "\x1b_Ga=t,f=32,s={w},v={h},i={id},q=2,m={more};{chunk}\x1b\\"
+                );
+            } else {
+                let _ = write!(out, "\x1b_Gm={more};{chunk}\x1b\\");
+            }
+        }
+    }
+
+    format!("{out}\x1b_Ga=p,i={id},p=1,c={ART_COLS},r={ART_ROWS},C=1,q=2\x1b\\")
+}
+
+fn sixel(art: &Art) -> String {
+    let (cell_w, cell_h) = cell_size();
+    #[allow(clippy::cast_possible_truncation)]
+    let image =
+        fit(art, (ART_COLS as u32) * cell_w, (ART_ROWS as u32) * cell_h);
+    let (w, h) = image.dimensions();
+
+    // quantize everything to a 6x6x6 color cube
+    let index = |p: &Rgba<u8>| {
+        let q = |c: u8| (usize::from(c) * 5 + 127) / 255;
+        q(p[0]) * 36 + q(p[1]) * 6 + q(p[2])
+    };
+    let pixels = image.pixels().map(index).collect::<Vec<_>>();
+
+    let mut out = format!("\x1bPq\"1;1;{w};{h}");
+    for i in 0..216 {
+        // sixel colors are percentages
+        let (red, green, blue) = (i / 36 * 20, i / 6 % 6 * 20, i % 6 * 20);
+        let _ = write!(out, "#{i};2;{red};{green};{blue}");
+    }
+
+    let (w, h) = (w as usize, h as usize);
+    for band in (0..h).step_by(6) {
+        let rows = (band..h).take(6).collect::<Vec<_>>();
+        let mut used = [false; 216];
+        for y in &rows {
+            for x in 0..w {
+                used[pixels[y * w + x]] = true;
+            }
+        }
+
+        // one pass over the band per color, with run-length encoding
+        let mut first = true;
+        for color in (0..216).filter(|c| used[*c]) {
+            if !first {
+                out.push('$');
+            }
+            first = false;
+            let _ = write!(out, "#{color}");
+
+            let mut run = (0, '?');
+            for x in 0..w {
+                let bits = rows
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, y)| pixels[*y * w + x] == color)
+                    .fold(0, |bits, (dy, _)| bits | (1 << dy));
+                let ch = char::from(63 + bits);
+                if ch == run.1 {
+                    run.0 += 1;
+                } else {
+                    push_run(&mut out, run);
+                    run = (1, ch);
+                }
+            }
+            push_run(&mut out, run);
+        }
+        out.push('-');
+    }
+    out + "\x1b\\"
+}
+
+fn push_run(out: &mut String, (count, ch): (usize, char)) {
+    match count {
+        0 => {}
+        1..=3 => out.extend(std::iter::repeat_n(ch, count)),
+        _ => {
+            let _ = write!(out, "!{count}{ch}");
+        }
+    }
+}
+
+fn blocks(art: &Art) -> Vec<String> {
+    #[allow(clippy::cast_possible_truncation)]
+    let image = fit(art, ART_COLS as u32, (ART_ROWS * 2) as u32);
+
+    // each cell shows two pixels, the top one in front of the bottom one
+    (0..image.height())
+        .step_by(2)
+        .map(|y| {
+            let mut line = String::new();
+            for x in 0..image.width() {
+                let top = image.get_pixel(x, y);
+                let bottom = image.get_pixel_checked(x, y + 1).unwrap_or(top);
+                let _ = write!(
+                    line,
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀",
+                    top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]
+                );
+            }
+            line + "\x1b[0m"
+        })
+        .collect()
+}
+
+fn ascii(art: &Art) -> Vec<String> {
+    const RAMP: &[u8] = b" .:-=+*#%@";
+
+    // cells are about twice as tall as they are wide, so squash the art
+    #[allow(clippy::cast_possible_truncation)]
+    let image = DynamicImage::ImageRgba8((*art.image).clone())
+        .resize_exact(ART_COLS as u32, ART_ROWS as u32, FilterType::Triangle)
+        .to_rgba8();
+    (0..image.height())
+        .map(|y| {
+            (0..image.width())
+                .map(|x| {
+                    let p = image.get_pixel(x, y);
+                    let luma = (u32::from(p[0]) * 3
+                        + u32::from(p[1]) * 6
+                        + u32::from(p[2]))
+                        / 10;
+                    char::from(RAMP[luma as usize * (RAMP.len() - 1) / 255])
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// scale the art to fit in a box, keeping its shape
+fn fit(art: &Art, w: u32, h: u32) -> RgbaImage {
+    DynamicImage::ImageRgba8((*art.image).clone())
+        .resize(w.max(1), h.max(1), FilterType::Triangle)
+        .to_rgba8()
+}
+
+// size of one terminal cell in pixels, so sixels come out the right size
+fn cell_size() -> (u32, u32) {
+    let mut ws = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: TIOCGWINSZ only writes into the struct we hand it
+    let ok =
+        unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) };
+    if ok == 0 && ws.ws_xpixel > 0 && ws.ws_ypixel > 0 && ws.ws_col > 0 {
+        (
+            u32::from(ws.ws_xpixel / ws.ws_col),
+            u32::from(ws.ws_ypixel / ws.ws_row.max(1)),
+        )
+    } else {
+        (10, 20)
+    }
+}
@@ -0,0 +1,97 @@
+use crate::common::BarStyle;
+
+use std::cmp::min;
+
+// partial blocks, from one eighth up to a full cell
+const EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+// braille cells with no, one or both columns filled
+const BRAILLE: [char; 3] = ['⣀', '⡇', '⣿'];
+
+// draw a progress bar exactly `width` columns wide. `ratio` is how far
+// through the song we are, or None if we don't know.
+pub fn render(style: BarStyle, width: usize, ratio: Option<f64>) -> String {
+    if width == 0 {
+        return String::new();
+    }
+    match style {
+        BarStyle::Ascii => ascii(width, ratio),
+        BarStyle::Blocks => blocks(width, ratio),
+        BarStyle::Braille => braille(width, ratio),
+        BarStyle::Line => line(width, ratio),
+    }
+}
+
+fn ascii(width: usize, ratio: Option<f64>) -> String {
+    let Some(ratio) = ratio else {
+        // if we can't get the time, throw up a default
+        return if width < 2 {
+            " ".repeat(width)
+        } else {
+            format!("[{}]", " ".repeat(width - 2))
+        };
+    };
+
+    // add one to make sure the bar is never empty,
+    // and that it looks full at the end of songs
+    let full = min(width, steps(width, 1, ratio) + 1);
+    let empty = width - full;
+
+    // careful logic for edge cases
+    // [===>   ]
+    let (open, full) = match full {
+        0 | 1 => ("", 0),
+        2 => ("[", 0),
+        _ => ("[", full - 2),
+    };
+    let (close, empty) = match empty {
+        0 => ("", 0),
+        _ => ("]", empty - 1),
+    };
+
+    format!("{open}{}>{}{close}", "=".repeat(full), " ".repeat(empty))
+}
+
+fn blocks(width: usize, ratio: Option<f64>) -> String {
+    let eighths = steps(width, 8, ratio.unwrap_or_default());
+    let (full, part) = (eighths / 8, eighths % 8);
+
+    let mut bar = EIGHTHS[7].to_string().repeat(full);
+    if part > 0 {
+        bar.push(EIGHTHS[part - 1]);
+    }
+    let empty = width - full - usize::from(part > 0);
+    bar + &" ".repeat(empty)
+}
+
+fn braille(width: usize, ratio: Option<f64>) -> String {
+    let halves = steps(width, 2, ratio.unwrap_or_default());
+    let (full, part) = (halves / 2, halves % 2);
+
+    let mut bar = BRAILLE[2].to_string().repeat(full);
+    if part > 0 {
+        bar.push(BRAILLE[1]);
+    }
+    let empty = width - full - part;
+    bar + &BRAILLE[0].to_string().repeat(empty)
+}
+
+fn line(width: usize, ratio: Option<f64>) -> String {
+    let Some(ratio) = ratio else {
+        return "─".repeat(width);
+    };
+
+    // the knob takes up a cell of its own
+    let done = min(width - 1, steps(width, 1, ratio));
+    format!("{}●{}", "━".repeat(done), "─".repeat(width - 1 - done))
+}
+
+// how many sub-cell steps of the bar should be filled
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn steps(width: usize, per_cell: usize, ratio: f64) -> usize {
+    let total = width * per_cell;
+    min(total, (total as f64 * ratio.clamp(0.0, 1.0)) as usize)
+}
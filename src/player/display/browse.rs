@@ -0,0 +1,269 @@
+use super::{MusicData, UNKNOWN};
+use crate::common::Action;
+use crate::player::browse::playlists::Prompt;
+use crate::player::browse::{self, files, Item};
+use crate::player::text;
+use crate::player::view::{SharedView, View, ViewState};
+
+use std::sync::Mutex;
+
+use clap::ValueEnum;
+use mpd::{Client, Song};
+
+const COL_TITLE: &str = "\x1b[1m"; // bold
+const COL_SELECT: &str = "\x1b[7m"; // reverse
+const COL_HINT: &str = "\x1b[2m"; // dim
+const COL_ERROR: &str = "\x1b[1;41m"; // bold, red background
+const COL_END: &str = "\x1b[0m"; // reset
+
+// the keys worth knowing about in a list, shown at the bottom
+const HINTS: [Action; 6] = [
+    Action::Open,
+    Action::Back,
+    Action::Add,
+    Action::AddAll,
+    Action::Insert,
+    Action::Replace,
+];
+const PLAYLIST_HINTS: [Action; 9] = [
+    Action::Open,
+    Action::Back,
+    Action::Replace,
+    Action::Add,
+    Action::SavePlaylist,
+    Action::Rename,
+    Action::Remove,
+    Action::MoveUp,
+    Action::MoveDown,
+];
+
+impl MusicData {
+    // a list to pick from, with where we are on top and keys at the bottom
+    pub(super) fn print_browser(
+        &self,
+        height: u32,
+        width: usize,
+    ) -> Vec<String> {
+        let mut view = self.view.lock().expect("can't lock view");
+        let rows = (height as usize).saturating_sub(2);
+        view.page = rows;
+        let Some(browser) = view.browser() else {
+            return Vec::new();
+        };
+
+        let (items, cursor) = browser
+            .level()
+            .map_or((&[][..], 0), |l| (l.items.as_slice(), l.cursor));
+        let count =
+            format!("{}/{}", (cursor + 1).min(items.len()), items.len());
+        let (title, right) = match (view.view, &view.playlists.prompt) {
+            (View::Search, _) => Self::search_prompt(&view, count, width),
+            (View::Playlists, Some(prompt)) => {
+                (Self::question(prompt, &view.playlists.typed), count)
+            }
+            _ => (browser.path().join(" › "), count),
+        };
+        let title = text::truncate(
+            &title,
+            width.saturating_sub(text::width(&right) + 1),
+        );
+        let gap =
+            width.saturating_sub(text::width(&title) + text::width(&right));
+
+        let mut lines = vec![format!(
+            "{COL_TITLE}{title}{}{right}{COL_END}",
+            " ".repeat(gap)
+        )];
+
+        // keep the cursor in the middle, when we can
+        let start = cursor
+            .saturating_sub(rows / 2)
+            .min(items.len().saturating_sub(rows));
+        let padding = items.len().to_string().len().try_into().unwrap_or(0);
+        lines.extend(items.iter().enumerate().skip(start).take(rows).map(
+            |(i, item)| {
+                let line = text::truncate(
+                    &self.row_text(&view, item, i, padding),
+                    width,
+                );
+                if i == cursor {
+                    let pad = width.saturating_sub(text::width(&line));
+                    format!("{COL_SELECT}{line}{}{COL_END}", " ".repeat(pad))
+                } else {
+                    line
+                }
+            },
+        ));
+        lines.resize(rows + 1, String::new());
+
+        let footer = self.footer(&view);
+        drop(view);
+        lines.push(format!(
+            "{COL_HINT}{}{COL_END}",
+            text::truncate(&footer, width)
+        ));
+        lines
+    }
+
+    // stored playlists again, if they're being shown, since they've changed
+    pub(super) fn update_playlists(
+        &self,
+        client: &Mutex<Client>,
+        address: &str,
+    ) {
+        let view = self.view.lock().expect("can't lock view");
+        if view.playlists.browser.is_empty() {
+            return;
+        }
+        let open = view.playlists.open().map(ToString::to_string);
+        drop(view);
+
+        let mut conn = client.lock().expect("can't lock client");
+        let fetched = browse::playlists::fetch(&mut conn, open.as_deref());
+        drop(conn);
+
+        let mut view = self.view.lock().expect("can't lock view");
+        match fetched {
+            Ok(fetched) => view.playlists.update(fetched),
+            Err(e) => view.playlists.browser.message = Some(e),
+        }
+        drop(view);
+        browse::playlists::summarize_later(
+            SharedView::clone(&self.view),
+            address.into(),
+        );
+    }
+
+    // what's been typed, and anything wrong with it instead of the count
+    fn search_prompt(
+        view: &ViewState,
+        count: String,
+        width: usize,
+    ) -> (String, String) {
+        let cursor = if view.search.typing { "_" } else { "" };
+        let right = view.search.error.as_ref().map_or(count, |e| {
+            format!("{COL_ERROR}{}{COL_END}", text::truncate(e, width / 2))
+        });
+        (format!("Search: {}{cursor}", view.search.query), right)
+    }
+
+    fn question(prompt: &Prompt, typed: &str) -> String {
+        match prompt {
+            Prompt::Save => format!("Save the queue as: {typed}_"),
+            Prompt::Rename(name) => format!("Rename {name} to: {typed}_"),
+            Prompt::Delete(name) => format!("Delete {name}? (y/n)"),
+        }
+    }
+
+    fn row_text(
+        &self,
+        view: &ViewState,
+        item: &Item,
+        i: usize,
+        padding: u32,
+    ) -> String {
+        match item {
+            // numbered like the queue, since there's no album order
+            Item::Song(song)
+                if matches!(view.view, View::Search | View::Playlists) =>
+            {
+                self.format_song(
+                    song,
+                    (i + 1).try_into().unwrap_or(u32::MAX),
+                    padding,
+                    false,
+                    None,
+                )
+            }
+            Item::Playlist(name) if view.view == View::Playlists => {
+                let Some((songs, time)) = view.playlists.summaries.get(name)
+                else {
+                    return name.clone();
+                };
+                let s = if *songs == 1 { "" } else { "s" };
+                let time =
+                    Self::get_pretty_time(Some(*time)).unwrap_or_default();
+                format!("{name}  {COL_HINT}{songs} song{s}, {time}{COL_END}")
+            }
+            _ => self.item_text(item, view.view),
+        }
+    }
+
+    fn item_text(&self, item: &Item, view: View) -> String {
+        let curr = |song: &Song| {
+            if song.file == self.song.file {
+                '>'
+            } else {
+                ' '
+            }
+        };
+        match item {
+            Item::Tag { value, .. } => value.clone(),
+            Item::Directory(path) => format!("{}/", files::name(path)),
+            Item::Playlist(path) => {
+                format!("{}  {COL_HINT}playlist{COL_END}", files::name(path))
+            }
+            // tags can't be trusted in here, that's why we're here
+            Item::Song(song) if view == View::Files => {
+                format!("{} {}", curr(song), files::name(&song.file))
+            }
+            Item::Song(song) => {
+                let track = browse::tag(song, "track")
+                    .and_then(|t| t.split('/').next())
+                    .unwrap_or_default();
+                let tags = self
+                    .format
+                    .iter()
+                    .filter(|t| *t != "eta")
+                    .map(|t| {
+                        self.song_tag(song, t).unwrap_or_else(|| UNKNOWN.into())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("  *  ");
+                format!("{} {track:>2}  {tags}", curr(song))
+            }
+        }
+    }
+
+    // how to answer whatever we're asking, or what happened last, or keys
+    fn footer(&self, view: &ViewState) -> String {
+        match view.view {
+            View::Search if view.search.typing => {
+                "tag:words looks in one tag, (...) is a filter expression, \
+                 enter goes to the results"
+                    .into()
+            }
+            View::Playlists if view.playlists.prompt.is_some() => {
+                let message = view.playlists.browser.message.as_deref();
+                let hint = match view.playlists.prompt {
+                    Some(Prompt::Delete(_)) => "y to delete, n to keep it",
+                    _ => "enter to go ahead, esc to cancel",
+                };
+                message.map_or_else(|| hint.into(), Into::into)
+            }
+            current => view
+                .browser()
+                .and_then(|b| b.message.clone())
+                .unwrap_or_else(|| {
+                    if current == View::Playlists {
+                        self.hints(&PLAYLIST_HINTS)
+                    } else {
+                        self.hints(&HINTS)
+                    }
+                }),
+        }
+    }
+
+    // "enter open  backspace back  a add ..." from the keymap
+    fn hints(&self, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .filter_map(|action| {
+                let (key, _) = *self.keymap.keys(*action).first()?;
+                let name = action.to_possible_value()?.get_name().to_string();
+                Some(format!("{} {name}", key.name()))
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
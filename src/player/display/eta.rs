@@ -0,0 +1,96 @@
+use super::MusicData;
+
+use std::mem;
+use std::ops::Range;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use mpd::State;
+
+impl MusicData {
+    // how long the whole queue is
+    pub(super) fn queue_time(&self) -> Duration {
+        self.queue.iter().filter_map(|s| s.duration).sum()
+    }
+
+    // how long until the queue runs out, counting from the current song
+    pub(super) fn queue_left(&self) -> Option<Duration> {
+        let pos = self.queue_track?.pos as usize;
+        let curr = self
+            .time_total?
+            .saturating_sub(self.elapsed().unwrap_or_default());
+        let rest = self.queue.get(pos + 1..).unwrap_or_default();
+        let rest = rest
+            .iter()
+            .filter_map(|s| s.duration)
+            .map(|d| d.saturating_sub(self.fade()))
+            .sum::<Duration>();
+        Some(curr + rest)
+    }
+
+    // with random on, the server picks what's next, so we can only guess
+    pub(super) fn is_guess(&self) -> bool {
+        *self.ersc_opts.get(1).unwrap_or(&false)
+    }
+
+    // the wall-clock time each song in the queue will start, if it will.
+    // working out a time of day isn't free, so it's only done when the
+    // format asks for it, and only for the rows that can be seen.
+    pub(super) fn etas(&self, rows: Range<usize>) -> Vec<Option<String>> {
+        let mut etas = vec![None; self.queue.len()];
+        if !self.format.iter().any(|t| t == "eta") {
+            return etas;
+        }
+        let Some(pos) = self.queue_track.map(|p| p.pos as usize) else {
+            return etas;
+        };
+        if self.state != State::Play {
+            return etas;
+        }
+
+        // songs that already played won't start again
+        for eta in etas.iter_mut().take(pos) {
+            *eta = Some(String::new());
+        }
+
+        let prefix = if self.is_guess() { "~" } else { "" };
+        let mut start = SystemTime::now() - self.elapsed().unwrap_or_default();
+        for (i, song) in self.queue.iter().enumerate().take(rows.end).skip(pos)
+        {
+            if rows.contains(&i) {
+                etas[i] = Some(format!("{prefix}{}", clock(start)));
+            }
+            // songs overlap by the crossfade
+            start += song
+                .duration
+                .unwrap_or_default()
+                .saturating_sub(self.fade());
+        }
+        etas
+    }
+
+    fn fade(&self) -> Duration {
+        self.crossfade.unwrap_or_default()
+    }
+}
+
+// the queue rows that might be on screen, with `height` lines of it shown
+// around `centre`
+pub(super) const fn around(centre: usize, height: u32) -> Range<usize> {
+    let height = height as usize;
+    centre.saturating_sub(height)..centre.saturating_add(height)
+}
+
+// local time of day, like "21:05"
+fn clock(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .try_into()
+        .unwrap_or_default();
+    // SAFETY: tm is plain data, so all zeroes is a valid value
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    // SAFETY: localtime_r only writes into the struct we hand it
+    unsafe { libc::localtime_r(&raw const secs, &raw mut tm) };
+    format!("{:02}:{:02}", tm.tm_hour, tm.tm_min)
+}
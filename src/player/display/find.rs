@@ -0,0 +1,180 @@
+use super::{MusicData, UNKNOWN};
+use crate::common::{Action, Key};
+use crate::player::text;
+use crate::player::view::ViewState;
+
+use mpd::Song;
+
+const COL_TITLE: &str = "\x1b[1m"; // bold
+const COL_SELECT: &str = "\x1b[7m"; // reverse
+const COL_MATCH: &str = "\x1b[1;33m"; // bold yellow
+const COL_UNMATCH: &str = "\x1b[22;39m"; // normal weight and color
+const COL_HINT: &str = "\x1b[2m"; // dim
+const COL_END: &str = "\x1b[0m"; // reset
+
+impl MusicData {
+    // the queue, narrowed down to the songs that match what's been typed
+    pub(super) fn print_find(&self, height: u32, width: usize) -> Vec<String> {
+        let mut view = self.view.lock().expect("can't lock view");
+        let rows = (height as usize).saturating_sub(2);
+        view.page = rows;
+
+        self.fill_find(&mut view);
+        view.find.search();
+        let find = &view.find;
+
+        let count = format!(
+            "{}/{}",
+            (find.selected + 1).min(find.matches.len()),
+            find.matches.len()
+        );
+        let title = text::truncate(
+            &format!("Find: {}_", find.query),
+            width.saturating_sub(text::width(&count) + 1),
+        );
+        let gap =
+            width.saturating_sub(text::width(&title) + text::width(&count));
+        let mut lines = vec![format!(
+            "{COL_TITLE}{title}{}{count}{COL_END}",
+            " ".repeat(gap)
+        )];
+
+        // only the rows on screen get drawn, however many match
+        let playing = self.song.place.map(|p| p.pos as usize);
+        let padding = find.total().to_string().len();
+        let start = find
+            .selected
+            .saturating_sub(rows / 2)
+            .min(find.matches.len().saturating_sub(rows));
+        for (i, (pos, hits)) in
+            find.matches.iter().enumerate().skip(start).take(rows)
+        {
+            let curr = if Some(*pos) == playing { '>' } else { ' ' };
+            let row = highlight(find.row(*pos).unwrap_or_default(), hits);
+            let line = text::truncate(
+                &format!("{curr} {:>padding$}  {row}", pos + 1),
+                width,
+            );
+            lines.push(if i == find.selected {
+                let pad = width.saturating_sub(text::width(&line));
+                format!("{COL_SELECT}{line}{}{COL_END}", " ".repeat(pad))
+            } else {
+                line
+            });
+        }
+        drop(view);
+        lines.resize(rows + 1, String::new());
+
+        // enter always plays in here, whatever else it's bound to
+        let key = |action| {
+            self.keymap
+                .keys(action)
+                .iter()
+                .find(|(k, _)| *k != Key::Enter)
+                .map_or_else(String::new, |(k, _)| k.name())
+        };
+        let footer = format!(
+            "enter play  {} show  {} next  {} previous  esc close",
+            key(Action::Open),
+            key(Action::NextMatch),
+            key(Action::PrevMatch),
+        );
+        lines.push(format!(
+            "{COL_HINT}{}{COL_END}",
+            text::truncate(&footer, width)
+        ));
+        lines
+    }
+
+    // tab and shift-tab go through the matches in the queue too, once the
+    // finder's closed, so they have to keep up with it
+    pub(super) fn update_find(&self) {
+        let mut view = self.view.lock().expect("can't lock view");
+        if !view.find.query.is_empty() {
+            self.fill_find(&mut view);
+        }
+    }
+
+    // making rows is the slow part, so only when the queue changes
+    fn fill_find(&self, view: &mut ViewState) {
+        if view.find.needs_rows(self.queue_version) {
+            let made = self.queue.iter().map(|s| self.find_row(s)).collect();
+            view.find.set_rows(made, self.queue_version);
+        }
+    }
+
+    // a queue row without its number, as plain text. times of day go out
+    // of date, so they're left out.
+    fn find_row(&self, song: &Song) -> String {
+        let tags = self
+            .format
+            .iter()
+            .enumerate()
+            .filter(|(i, tag)| {
+                *tag != "eta"
+                    && (self.options.verbose
+                        || !self.verbose_tags.get(*i).copied().unwrap_or(false))
+            })
+            .map(|(_, tag)| {
+                self.song_tag(song, tag).unwrap_or_else(|| UNKNOWN.into())
+            })
+            .collect::<Vec<_>>()
+            .join("  *  ");
+        text::strip_ansi(&tags)
+    }
+}
+
+// a row with the characters that matched picked out
+fn highlight(row: &str, hits: &[usize]) -> String {
+    let mut out = String::with_capacity(row.len() + hits.len() * 16);
+    let mut hits = hits.iter().peekable();
+    for (i, c) in row.chars().enumerate() {
+        if hits.next_if_eq(&&i).is_some() {
+            out.push_str(COL_MATCH);
+            out.push(c);
+            out.push_str(COL_UNMATCH);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // matched characters in brackets, to read more easily
+    fn marked(row: &str, hits: &[usize]) -> String {
+        highlight(row, hits)
+            .replace(COL_MATCH, "[")
+            .replace(COL_UNMATCH, "]")
+    }
+
+    #[test]
+    fn picks_out_the_matches() {
+        assert_eq!(marked("Blue", &[0, 3]), "[B]lu[e]");
+        assert_eq!(marked("Blue", &[]), "Blue");
+        assert_eq!(marked("", &[]), "");
+    }
+
+    #[test]
+    fn counts_chars_not_bytes() {
+        assert_eq!(marked("東京 Tokyo", &[1, 3]), "東[京] [T]okyo");
+        // lowercase 'İ' is two chars, but the finder only looks at one
+        assert_eq!(marked("İstanbul", &[0]), "[İ]stanbul");
+    }
+
+    #[test]
+    fn ignores_hits_past_the_end() {
+        assert_eq!(marked("ab", &[1, 5]), "a[b]");
+    }
+
+    #[test]
+    fn the_text_is_unchanged() {
+        let row = "Carey  *  Joni Mitchell";
+        let lit = highlight(row, &[0, 10, 11]);
+        assert_eq!(text::strip_ansi(&lit), row);
+        assert_eq!(text::width(&lit), text::width(row));
+    }
+}
@@ -0,0 +1,213 @@
+use super::{eta, MusicData, UNKNOWN};
+use crate::common::Align;
+use crate::player::text;
+
+use std::time::Duration;
+
+use mpd::Song;
+
+const COL_HEAD: &str = "\x1b[1m"; // bold
+const COL_SELECT: &str = "\x1b[1;4m"; // bold underline
+const COL_CURR: &str = "\x1b[7m"; // reverse
+const COL_END: &str = "\x1b[0m"; // reset
+
+// a run of songs from the same album, next to each other in the queue
+struct Group<'a> {
+    key: String,
+    start: usize,
+    songs: &'a [Song],
+}
+
+impl MusicData {
+    pub(super) fn print_groups(
+        &self,
+        queue_height: u32,
+        width: usize,
+    ) -> Vec<String> {
+        let groups = self.groups();
+        let song_pos = self.song.place.map_or(0, |p| p.pos as usize);
+        let playing = groups
+            .iter()
+            .rposition(|g| g.start <= song_pos)
+            .unwrap_or_default();
+
+        // keep the cursor on a group that exists
+        let mut view = self.view.lock().expect("can't lock view");
+        let last = groups.len().saturating_sub(1);
+        // a song that was found takes the cursor to its group
+        if let Some(found) = view.focus.take() {
+            let group = groups.iter().rposition(|g| g.start <= found);
+            view.cursor =
+                group.unwrap_or_default().cast_signed() - playing.cast_signed();
+        }
+        let selected = playing.saturating_add_signed(view.cursor).min(last);
+        view.cursor = selected.cast_signed() - playing.cast_signed();
+
+        // only now do we know which group input wanted to collapse
+        if view.collapse {
+            view.collapse = false;
+            if let Some(key) = groups.get(selected).map(|g| &g.key) {
+                if !view.collapsed.remove(key) {
+                    view.collapsed.insert(key.clone());
+                }
+            }
+        }
+        let collapsed = view.collapsed.clone();
+        drop(view);
+
+        // the songs on screen are around the playing one, or the start of
+        // the group under the cursor
+        let centre = groups
+            .get(selected)
+            .filter(|_| selected != playing)
+            .map_or(song_pos, |g| g.start);
+        let etas = self.etas(eta::around(centre, queue_height));
+
+        let mut lines = Vec::new();
+        // the line we keep in the middle of the screen
+        let mut focus = 0;
+        for (i, group) in groups.iter().enumerate() {
+            let is_collapsed = collapsed.contains(&group.key);
+            if i == selected {
+                focus = lines.len();
+            }
+            lines.push(Self::group_header(
+                group,
+                is_collapsed,
+                i == selected,
+                width,
+            ));
+            if is_collapsed {
+                continue;
+            }
+
+            for (j, song) in group.songs.iter().enumerate() {
+                let is_curr = group.start + j == song_pos;
+                if is_curr && i == playing && selected == playing {
+                    focus = lines.len();
+                }
+                let eta = etas.get(group.start + j).and_then(Option::as_ref);
+                lines.push(self.group_row(song, is_curr, eta, width));
+            }
+        }
+
+        let total = lines.len().try_into().unwrap_or(0);
+        let focus = focus.try_into().unwrap_or(0);
+        Self::filter_queue(&lines, queue_height, total, focus).to_vec()
+    }
+
+    fn groups(&self) -> Vec<Group<'_>> {
+        let mut groups: Vec<Group> = Vec::new();
+        for (i, song) in self.queue.iter().enumerate() {
+            let key = Self::group_key(song);
+            match groups.last_mut() {
+                Some(group) if group.key == key => {
+                    group.songs = &self.queue[group.start..=i];
+                }
+                _ => groups.push(Group {
+                    key,
+                    start: i,
+                    songs: &self.queue[i..=i],
+                }),
+            }
+        }
+        groups
+    }
+
+    fn group_key(song: &Song) -> String {
+        let artist = Self::album_artist(song).unwrap_or_default();
+        let album = Self::get_metadata(song, "album").unwrap_or_default();
+        format!("{artist}\0{album}")
+    }
+
+    fn album_artist(song: &Song) -> Option<String> {
+        Self::get_metadata(song, "albumartist").or_else(|| song.artist.clone())
+    }
+
+    fn group_header(
+        group: &Group,
+        collapsed: bool,
+        selected: bool,
+        width: usize,
+    ) -> String {
+        let first = &group.songs[0];
+        let artist =
+            Self::album_artist(first).unwrap_or_else(|| UNKNOWN.into());
+        let album = Self::get_metadata(first, "album")
+            .unwrap_or_else(|| UNKNOWN.into());
+        let date = Self::get_metadata(first, "date")
+            .map_or_else(String::new, |d| format!(" ({d})"));
+
+        let count = group.songs.len();
+        let total = group
+            .songs
+            .iter()
+            .filter_map(|s| s.duration)
+            .sum::<Duration>();
+        let info = format!(
+            "{count} track{}, {}",
+            if count == 1 { "" } else { "s" },
+            Self::get_pretty_time(Some(total)).unwrap_or_default()
+        );
+
+        let arrow = if collapsed { '▸' } else { '▾' };
+        let title = format!("{arrow} {artist} - {album}{date}");
+        let col = if selected { COL_SELECT } else { COL_HEAD };
+        format!("{col}{}{COL_END}", Self::spread(&title, &info, width))
+    }
+
+    fn group_row(
+        &self,
+        song: &Song,
+        is_curr: bool,
+        eta: Option<&String>,
+        width: usize,
+    ) -> String {
+        let track = Self::get_metadata(song, "track")
+            .map(|t| t.split('/').next().unwrap_or_default().to_string())
+            .unwrap_or_default();
+
+        // the header already says the album, and whose it is, so the artist
+        // only shows when it isn't the album's
+        let album_artist = Self::album_artist(song);
+        let title = self
+            .format
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                self.options.verbose
+                    || !self.verbose_tags.get(*i).copied().unwrap_or(false)
+            })
+            .filter_map(|(_, tag)| match tag.as_str() {
+                "album" | "albumartist" => None,
+                "artist" if song.artist == album_artist => None,
+                "title" => Some(Some(Self::read_title(song))),
+                "eta" => Some(eta.cloned()),
+                _ => Some(self.song_tag(song, tag)),
+            })
+            .map(|v| v.unwrap_or_else(|| UNKNOWN.into()))
+            .collect::<Vec<_>>()
+            .join("  *  ");
+
+        let time = Self::get_pretty_time(song.duration).unwrap_or_default();
+        let curr = if is_curr { '>' } else { ' ' };
+        let line = Self::spread(
+            &format!("{curr}   {track:>2}  {title}"),
+            &time,
+            width,
+        );
+        if is_curr {
+            format!("{COL_CURR}{line}{COL_END}")
+        } else {
+            line
+        }
+    }
+
+    // put `left` and `right` on either end of a line, cutting `left` short
+    // if they don't both fit
+    fn spread(left: &str, right: &str, width: usize) -> String {
+        let space = width.saturating_sub(text::width(right) + 2);
+        let left = text::align(left, space, Align::Left);
+        text::truncate(&format!("{left}  {right}"), width)
+    }
+}
@@ -0,0 +1,148 @@
+use super::MusicData;
+use crate::common::{Action, Key, Output};
+use crate::player::keymap::Category;
+use crate::player::text;
+use crate::player::view::View;
+
+use clap::ValueEnum;
+
+const COL_TITLE: &str = "\x1b[1;4m"; // bold underline
+const COL_KEY: &str = "\x1b[1m"; // bold
+const COL_OFF: &str = "\x1b[2m"; // dim
+const COL_CREDIT: &str = "\x1b[1;35m"; // bold magenta
+const COL_END: &str = "\x1b[0m"; // reset
+
+// keys and descriptions line up after this many columns
+const KEY_WIDTH: usize = 14;
+
+impl MusicData {
+    // the help screen, built from whatever the keys are actually bound to
+    pub(super) fn print_help(&self, height: u32, width: usize) -> Vec<String> {
+        let mut view = self.view.lock().expect("can't lock view");
+        let filter = view.filter.to_lowercase();
+        let context = view.prev;
+
+        // scrolling is what you want to know about in views that scroll
+        let mut categories = Category::ALL.to_vec();
+        let scrolls = [Action::ScrollDown, Action::Collapse]
+            .iter()
+            .any(|a| self.works_in(*a, context));
+        if scrolls {
+            categories.retain(|c| *c != Category::Scrolling);
+            categories.insert(0, Category::Scrolling);
+        }
+
+        let mut body = Vec::new();
+        let mut user = false;
+        for category in categories {
+            let lines = Action::value_variants()
+                .iter()
+                .filter(|a| Category::of(**a) == category)
+                .filter_map(|a| {
+                    let keys = self.keymap.keys(*a);
+                    user |= keys.iter().any(|(_, u)| *u);
+                    let line = self.help_line(*a, &keys, context);
+                    text::strip_ansi(&line)
+                        .to_lowercase()
+                        .contains(&filter)
+                        .then_some(line)
+                })
+                .collect::<Vec<_>>();
+            if lines.is_empty() {
+                continue;
+            }
+            body.push(format!("{COL_TITLE}{category:?}{COL_END}"));
+            body.extend(lines);
+            body.push(String::new());
+        }
+        if user {
+            body.push(format!("{COL_OFF}* your bindings{COL_END}"));
+        }
+        body.push(format!("{COL_CREDIT}~made by aurora~{COL_END}"));
+
+        // the title stays put, everything under it scrolls
+        let title = if view.filtering {
+            format!("{COL_KEY}Keys{COL_END}  filter: {}_", view.filter)
+        } else {
+            format!("{COL_KEY}Keys{COL_END}  {COL_OFF}type or / to filter, esc to close{COL_END}")
+        };
+        let page = (height as usize).saturating_sub(2);
+        view.page = page;
+        view.scroll = view.scroll.min(body.len().saturating_sub(page));
+        let scroll = view.scroll;
+        drop(view);
+
+        [title, String::new()]
+            .into_iter()
+            .chain(body.into_iter().skip(scroll).take(page))
+            .map(|line| text::truncate(&format!("  {line}"), width))
+            .collect()
+    }
+
+    fn help_line(
+        &self,
+        action: Action,
+        keys: &[(Key, bool)],
+        context: View,
+    ) -> String {
+        let (name, desc) = action.to_possible_value().map_or_else(
+            || (String::new(), String::new()),
+            |v| {
+                (
+                    v.get_name().to_string(),
+                    v.get_help().map(ToString::to_string).unwrap_or_default(),
+                )
+            },
+        );
+        let keys = if keys.is_empty() {
+            "(unbound)".into()
+        } else {
+            keys.iter()
+                .map(|(k, user)| {
+                    format!("{}{}", k.name(), if *user { "*" } else { "" })
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let dots = ".".repeat(KEY_WIDTH.saturating_sub(text::width(&keys) + 1));
+
+        // things that do nothing here are still listed, but faded
+        let col = if self.works_in(action, context) {
+            ""
+        } else {
+            COL_OFF
+        };
+        format!(
+            "{col}  {COL_KEY}{keys}{COL_END}{col} {dots}{desc} {COL_OFF}({name}){COL_END}"
+        )
+    }
+
+    fn works_in(&self, action: Action, view: View) -> bool {
+        let grouped = view == View::Queue && self.options.group;
+        match action {
+            Action::ScrollDown
+            | Action::ScrollUp
+            | Action::PageDown
+            | Action::PageUp => {
+                matches!(view, View::Lyrics | View::Find)
+                    || grouped
+                    || view.is_browser()
+            }
+            Action::Open => view.is_browser() || view == View::Find,
+            Action::NextMatch | Action::PrevMatch => {
+                matches!(view, View::Queue | View::Find)
+            }
+            Action::Add | Action::AddAll | Action::Insert | Action::Replace => {
+                view.is_browser()
+            }
+            Action::Back => view != View::Queue,
+            Action::Rename
+            | Action::Remove
+            | Action::MoveUp
+            | Action::MoveDown => view == View::Playlists,
+            Action::Collapse => grouped,
+            Action::WhereAmI => self.options.output == Output::Accessible,
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::MusicData;
+use crate::common::Event;
+
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mpd::State;
+
+#[allow(unused_imports)]
+use debug_print::{
+    debug_eprint as deprint, debug_eprintln as deprintln,
+    debug_print as dprint, debug_println as dprintln,
+};
+
+// how often to check whether a hook has finished
+const POLL: Duration = Duration::from_millis(50);
+// how close to its end a song has to be, to have finished rather than
+// been stopped
+const SLACK: Duration = Duration::from_secs(2);
+
+// the bits of state that hooks and screen readers care about, to compare
+// before and after
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watch {
+    pub file: String,
+    pub state: State,
+    pub rating: Option<String>,
+    pub volume: i8,
+    pub ersc: Vec<bool>,
+    pub crossfade: Option<Duration>,
+    pub error: Option<String>,
+    // whether the song is about to run out
+    pub ending: bool,
+}
+
+impl Watch {
+    pub fn song_changed(&self, before: &Self) -> bool {
+        self.file != before.file && !self.file.is_empty()
+    }
+
+    // a new song has a new rating, that isn't a change
+    pub fn rating_changed(&self, before: &Self) -> bool {
+        self.rating != before.rating && self.file == before.file
+    }
+}
+
+impl MusicData {
+    pub(super) fn watch(&self) -> Watch {
+        Watch {
+            file: self.song.file.clone(),
+            state: self.state,
+            rating: self.rating.clone(),
+            volume: self.volume,
+            ersc: self.ersc_opts.clone(),
+            crossfade: self.crossfade,
+            error: self.error.clone(),
+            ending: self
+                .elapsed()
+                .zip(self.time_total)
+                .is_some_and(|(elapsed, total)| elapsed + SLACK >= total),
+        }
+    }
+
+    // run the hooks for whatever changed since `before`
+    pub(super) fn fire_hooks(&self, before: &Watch, queue_changed: bool) {
+        let after = self.watch();
+        let mut events = Vec::new();
+
+        if after.song_changed(before) {
+            events.push(Event::Song);
+        }
+        if after.state != before.state {
+            events.push(match after.state {
+                State::Play => Event::Play,
+                State::Pause => Event::Pause,
+                State::Stop => Event::Stop,
+            });
+            // stopping is only the end if the song ran out, rather than
+            // someone pressing stop
+            if before.state == State::Play
+                && after.state == State::Stop
+                && (before.ending || after.file.is_empty())
+            {
+                events.push(Event::End);
+            }
+        }
+        if queue_changed {
+            events.push(Event::Queue);
+        }
+        if after.rating_changed(before) {
+            events.push(Event::Rating);
+        }
+        if after.volume != before.volume {
+            events.push(Event::Volume);
+        }
+
+        for event in events {
+            for hook in self.options.hooks.iter().filter(|h| h.event == event) {
+                dprintln!("[hook: {event:?}: {}]", hook.command);
+                self.run_hook(event, &hook.command);
+            }
+        }
+    }
+
+    // run a hook in the background, so a slow one never holds up drawing
+    fn run_hook(&self, event: Event, command: &str) {
+        let timeout = self.options.hook_timeout;
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(command)
+            .envs(self.hook_env(event))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            // so that a timeout kills anything the hook started, too
+            .process_group(0);
+
+        thread::spawn(move || {
+            let Ok(mut child) = cmd.spawn() else {
+                return;
+            };
+            let start = Instant::now();
+            while matches!(child.try_wait(), Ok(None)) {
+                if start.elapsed() >= timeout {
+                    dprintln!("[hook: timed out]");
+                    if let Ok(pid) = i32::try_from(child.id()) {
+                        // SAFETY: kill doesn't touch memory
+                        unsafe { libc::kill(-pid, libc::SIGKILL) };
+                    }
+                    let _ = child.wait();
+                    return;
+                }
+                thread::sleep(POLL);
+            }
+        });
+    }
+
+    // everything a hook might want to know, as environment variables
+    fn hook_env(&self, event: Event) -> Vec<(String, String)> {
+        let secs = |d: Option<Duration>| {
+            d.map_or_else(String::new, |d| d.as_secs().to_string())
+        };
+        let mut env = vec![
+            ("MPD_EVENT", format!("{event:?}").to_lowercase()),
+            (
+                "MPD_STATE",
+                match self.state {
+                    State::Play => "play",
+                    State::Pause => "pause",
+                    State::Stop => "stop",
+                }
+                .into(),
+            ),
+            ("MPD_FILE", self.song.file.clone()),
+            ("MPD_TITLE", self.title.clone().unwrap_or_default()),
+            ("MPD_ARTIST", self.artist.clone().unwrap_or_default()),
+            ("MPD_ALBUM", self.album.clone().unwrap_or_default()),
+            ("MPD_ELAPSED", secs(self.elapsed())),
+            ("MPD_DURATION", secs(self.time_total)),
+            (
+                "MPD_POSITION",
+                self.queue_track
+                    .map_or_else(String::new, |p| (p.pos + 1).to_string()),
+            ),
+            ("MPD_VOLUME", self.volume.to_string()),
+            ("MPD_RATING", self.rating.clone().unwrap_or_default()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect::<Vec<_>>();
+
+        // and every tag, like MPD_TAG_ALBUMARTIST
+        for (tag, value) in &self.song.tags {
+            let tag = tag
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+                .to_uppercase();
+            env.push((format!("MPD_TAG_{tag}"), value.clone()));
+        }
+        env
+    }
+}
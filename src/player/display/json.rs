@@ -0,0 +1,164 @@
+use super::MusicData;
+
+use std::collections::BTreeMap;
+
+use mpd::State;
+use serde::Serialize;
+
+// bump this whenever a field changes meaning or goes away. adding fields
+// doesn't need a bump, readers should ignore what they don't know.
+const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[allow(clippy::struct_excessive_bools)]
+// allow because these are the server's independent flags
+struct Snapshot<'a> {
+    version: u32,
+    state: &'static str,
+    song: Option<SongInfo<'a>>,
+    queue: QueueInfo,
+    elapsed: Option<f64>,
+    duration: Option<f64>,
+    volume: Option<i8>,
+    repeat: bool,
+    random: bool,
+    single: bool,
+    consume: bool,
+    crossfade: Option<u64>,
+    audio: Option<AudioInfo>,
+    bitrate: Option<u32>,
+    error: Option<&'a str>,
+    rating: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct AudioInfo {
+    rate: u32,
+    bits: u8,
+    channels: u8,
+}
+
+#[derive(Serialize)]
+struct SongInfo<'a> {
+    file: &'a str,
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    date: Option<&'a str>,
+    album_track: Option<u32>,
+    album_total: Option<u32>,
+    tags: BTreeMap<&'a str, &'a str>,
+}
+
+#[derive(Serialize)]
+struct QueueInfo {
+    position: Option<u32>,
+    next: Option<u32>,
+    length: u32,
+}
+
+// one block of the i3bar protocol
+#[derive(Serialize)]
+struct I3Block<'a> {
+    name: &'static str,
+    full_text: &'a str,
+}
+
+// waybar's custom module format
+#[derive(Serialize)]
+struct WaybarModule<'a> {
+    text: &'a str,
+    tooltip: &'a str,
+    class: &'static str,
+    percentage: u64,
+}
+
+impl MusicData {
+    pub(super) fn to_json(&self) -> String {
+        let song = (!self.song.file.is_empty()).then(|| SongInfo {
+            file: &self.song.file,
+            title: self.title.as_deref(),
+            artist: self.artist.as_deref(),
+            album: self.album.as_deref(),
+            date: self.date.as_deref(),
+            album_track: self.album_track,
+            album_total: self.album_total,
+            tags: self
+                .song
+                .tags
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+        });
+        let flag = |i| *self.ersc_opts.get(i).unwrap_or(&false);
+
+        let snapshot = Snapshot {
+            version: VERSION,
+            state: match self.state {
+                State::Play => "play",
+                State::Pause => "pause",
+                State::Stop => "stop",
+            },
+            song,
+            queue: QueueInfo {
+                position: self.queue_track.map(|p| p.pos + 1),
+                next: self
+                    .queue_next
+                    .filter(|_| !self.stops_after_this())
+                    .map(|p| p.pos + 1),
+                length: self.queue_total.unwrap_or_default(),
+            },
+            elapsed: self.elapsed().map(|t| t.as_secs_f64()),
+            duration: self.time_total.map(|t| t.as_secs_f64()),
+            // the server says -1 when there's no mixer
+            volume: (self.volume >= 0).then_some(self.volume),
+            repeat: flag(0),
+            random: flag(1),
+            single: flag(2),
+            consume: flag(3),
+            crossfade: self.crossfade.map(|t| t.as_secs()),
+            audio: self.audio.map(|a| AudioInfo {
+                rate: a.rate,
+                bits: a.bits,
+                channels: a.chans,
+            }),
+            bitrate: self.bitrate,
+            error: self.error.as_deref(),
+            rating: self.rating.as_deref(),
+        };
+        serde_json::to_string(&snapshot).expect("can't serialize state")
+    }
+}
+
+// the header that starts the i3bar protocol, before the endless array
+pub fn i3bar_header() -> String {
+    "{\"version\":1,\"click_events\":true}\n[".into()
+}
+
+// every status after the header is one element of the array
+pub fn i3bar(text: &str) -> String {
+    let block = I3Block {
+        name: "mpc-display-rs",
+        full_text: text,
+    };
+    let line = serde_json::to_string(&[block]).expect("can't serialize block");
+    format!("{line},")
+}
+
+impl MusicData {
+    pub(super) fn to_waybar(&self, text: &str, tooltip: &str) -> String {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let percentage = self.progress().map_or(0, |p| (p * 100.0) as u64);
+        let module = WaybarModule {
+            text,
+            tooltip,
+            class: match self.state {
+                State::Play => "playing",
+                State::Pause => "paused",
+                State::Stop => "stopped",
+            },
+            percentage,
+        };
+        serde_json::to_string(&module).expect("can't serialize module")
+    }
+}
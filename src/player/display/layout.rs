@@ -0,0 +1,169 @@
+use super::{art, bar, template, MusicData};
+use crate::common::{Align, Layout};
+use crate::player::text;
+
+use std::fmt::Write;
+
+const COL_TITLE: &str = "\x1b[1;34m"; // bold blue
+const COL_ARTIST: &str = "\x1b[1;36m"; // bold cyan
+const COL_ALBUM: &str = "\x1b[36m"; // cyan
+const COL_BAR: &str = "\x1b[35m"; // magenta
+const COL_RULE: &str = "\x1b[2m"; // dim
+const COL_ERROR: &str = "\x1b[1;41m"; // bold, red background
+const COL_END: &str = "\x1b[0m"; // reset
+
+// below these, layouts give up and become something simpler
+const SPLIT_MIN_WIDTH: usize = 100;
+const BIG_MIN_WIDTH: usize = 30;
+const BIG_MIN_HEIGHT: usize = 10;
+pub const QUEUE_MIN_HEIGHT: u32 = 3;
+
+// the header gets this much of a split screen
+const SPLIT_PERCENT: usize = 40;
+
+// the layout we can actually draw at this size. the header's height isn't
+// known yet, so top and bottom get checked again once it is.
+pub const fn fit(layout: Layout, width: usize, height: usize) -> Layout {
+    match layout {
+        Layout::Split if width < SPLIT_MIN_WIDTH => Layout::Top,
+        Layout::Big if width < BIG_MIN_WIDTH || height < BIG_MIN_HEIGHT => {
+            Layout::Header
+        }
+        _ => layout,
+    }
+}
+
+impl MusicData {
+    // the header wrapped (or scrolled) to a width, and how many lines that
+    // took
+    pub(super) fn wrapped_header(&self, width: usize) -> (String, u32) {
+        let header = self.print_header(width);
+        let header = self.options.marquee.map_or_else(
+            || textwrap::fill(&header, textwrap::Options::new(width)),
+            |speed| {
+                header
+                    .lines()
+                    .map(|line| self.marquee(line, width, speed))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+        );
+        let height = (1 + header.matches('\n').count())
+            .try_into()
+            .expect("can't cast header size");
+        (header, height)
+    }
+
+    // header on the left, queue on the right, with the art under the header
+    pub(super) fn print_split(&self, height: u32, width: usize) -> String {
+        let left_width = width * SPLIT_PERCENT / 100;
+        let right_width = width.saturating_sub(left_width + 3);
+
+        let (header, header_height) = self.wrapped_header(left_width);
+        let queue = self.print_queue(
+            height,
+            right_width.try_into().expect("nothing should be that big"),
+            0,
+        );
+        let left = header.split('\n').collect::<Vec<_>>();
+        let right = queue.split('\n').collect::<Vec<_>>();
+
+        let mut out = (0..height as usize)
+            .map(|i| {
+                format!(
+                    "{} {COL_RULE}│{COL_END} {}",
+                    text::align(
+                        left.get(i).unwrap_or(&""),
+                        left_width,
+                        Align::Left
+                    ),
+                    right.get(i).unwrap_or(&""),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // only if it fits under the header
+        if let Some(art) = &self.art {
+            let row = header_height as usize + 2;
+            if row + art::ART_ROWS <= height as usize + 1 {
+                out += &art::render(art, self.options.art, row, 1);
+            }
+        }
+        out
+    }
+
+    // just the current song, in the middle of the screen
+    pub(super) fn print_big(&self, height: u32, width: usize) -> String {
+        let field = |f: &str| self.field(f);
+        let bar_width = width.saturating_sub(4);
+
+        let mut lines = Vec::new();
+        if self.error.is_some() {
+            lines.push(template::fill(
+                &format!("{COL_ERROR}error: {{error}}{COL_END}"),
+                field,
+            ));
+            lines.push(String::new());
+        }
+        lines.extend([
+            template::fill("{state}", field),
+            String::new(),
+            template::fill(&format!("{COL_TITLE}{{title}}{COL_END}"), field),
+            template::fill(&format!("{COL_ARTIST}{{artist}}{COL_END}"), field),
+            template::fill(
+                &format!("{COL_ALBUM}{{album}} ({{date}}){COL_END}"),
+                field,
+            ),
+            String::new(),
+            format!(
+                "{COL_BAR}{}{COL_END}",
+                bar::render(self.options.bar, bar_width, self.progress())
+            ),
+            template::fill(
+                "{elapsed}/{duration}  #{queue_track}/{queue_total}",
+                field,
+            ),
+            String::new(),
+            template::fill("{ersc}, {volume}%{xfade}  {rating}", field),
+        ]);
+        if self.options.next {
+            lines.push(template::fill("Up next: {next}", field));
+        }
+
+        // the art goes on top, if there's room for it
+        let height = height as usize;
+        let art = self
+            .art
+            .as_ref()
+            .filter(|_| lines.len() + art::ART_ROWS < height);
+        let block = lines.len() + art.map_or(0, |_| art::ART_ROWS + 1);
+        let top = height.saturating_sub(block) / 2;
+
+        let mut out = "\n".repeat(top);
+        if art.is_some() {
+            out += &"\n".repeat(art::ART_ROWS + 1);
+        }
+        out += &lines
+            .iter()
+            .take(height.saturating_sub(top))
+            .map(|line| {
+                self.options.marquee.map_or_else(
+                    || line.clone(),
+                    |speed| self.marquee(line, width, speed),
+                )
+            })
+            .map(|line| text::align(&line, width, Align::Center))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let Some(art) = art {
+            let col = width.saturating_sub(art::ART_COLS) / 2 + 1;
+            let _ = write!(
+                out,
+                "{}",
+                art::render(art, self.options.art, top + 1, col)
+            );
+        }
+        out
+    }
+}
@@ -0,0 +1,203 @@
+use super::MusicData;
+use crate::player::text;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use mpd::Song;
+
+const COL_CURR: &str = "\x1b[1;35m"; // bold magenta
+const COL_PAST: &str = "\x1b[2m"; // dim
+const COL_END: &str = "\x1b[0m"; // reset
+
+const INDENT: &str = "  ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lyrics {
+    // lines with the time they start at, in order
+    Synced(Vec<(Duration, String)>),
+    Plain(Vec<String>),
+}
+
+impl Lyrics {
+    // look next to the song first, then in the lyrics directory
+    pub fn find(
+        song: &Song,
+        music_dir: Option<&Path>,
+        lyrics_dir: Option<&Path>,
+    ) -> Option<Self> {
+        if song.file.is_empty() {
+            return None;
+        }
+
+        let mut stems = Vec::new();
+        if let Some(dir) = music_dir {
+            stems.push(dir.join(&song.file).with_extension(""));
+        }
+        let lyrics_dir = lyrics_dir
+            .map(Path::to_path_buf)
+            .or_else(|| super::cache_dir().map(|d| d.join("lyrics")));
+        if let Some(dir) = lyrics_dir {
+            if let (Some(artist), Some(title)) = (&song.artist, &song.title) {
+                stems.push(dir.join(format!("{artist} - {title}")));
+            }
+            let base = Path::new(&song.file).with_extension("");
+            if let Some(name) = base.file_name() {
+                stems.push(dir.join(name));
+            }
+        }
+
+        stems.iter().find_map(|stem| {
+            ["lrc", "txt"].iter().find_map(|ext| {
+                let path = PathBuf::from(format!("{}.{ext}", stem.display()));
+                fs::read_to_string(path).ok().map(|s| Self::parse(&s))
+            })
+        })
+    }
+
+    // anything with timestamps is treated as lrc, everything else is plain
+    pub fn parse(contents: &str) -> Self {
+        let mut offset: i64 = 0;
+        let mut synced = Vec::new();
+
+        for line in contents.lines() {
+            let mut rest = line.trim();
+            let mut times = Vec::new();
+            while let Some((tag, after)) =
+                rest.strip_prefix('[').and_then(|r| r.split_once(']'))
+            {
+                if let Some(time) = parse_time(tag) {
+                    times.push(time);
+                } else if let Some(ms) = tag.strip_prefix("offset:") {
+                    offset = ms.trim().parse().unwrap_or(0);
+                }
+                rest = after;
+            }
+
+            let words = strip_word_times(rest);
+            for time in times {
+                synced.push((time, words.clone()));
+            }
+        }
+
+        if synced.is_empty() {
+            return Self::Plain(contents.lines().map(Into::into).collect());
+        }
+
+        // a positive offset makes the lyrics show up sooner
+        let shift = Duration::from_millis(offset.unsigned_abs());
+        for (time, _) in &mut synced {
+            *time = if offset > 0 {
+                time.saturating_sub(shift)
+            } else {
+                *time + shift
+            };
+        }
+        synced.sort_by_key(|(time, _)| *time);
+        Self::Synced(synced)
+    }
+}
+
+impl MusicData {
+    pub(super) fn print_lyrics(
+        &self,
+        queue_height: u32,
+        width: usize,
+    ) -> Vec<String> {
+        let height = queue_height as usize;
+        let Some(lyrics) = &self.lyrics else {
+            return vec![format!("{INDENT}{COL_PAST}no lyrics found{COL_END}")];
+        };
+        let width = width.saturating_sub(INDENT.len());
+
+        match lyrics.as_ref() {
+            Lyrics::Synced(lines) => {
+                // the current line is the last one that has started
+                let elapsed = self.elapsed().unwrap_or_default();
+                let curr = lines.iter().rposition(|(t, _)| *t <= elapsed);
+
+                let total = lines.len().try_into().unwrap_or(0);
+                let head = Self::get_centered_index(
+                    queue_height,
+                    total,
+                    curr.unwrap_or_default().try_into().unwrap_or(0),
+                ) as usize;
+
+                lines
+                    .iter()
+                    .enumerate()
+                    .skip(head)
+                    .take(height)
+                    .map(|(i, (_, line))| {
+                        let line = text::truncate(line, width);
+                        let col = match curr {
+                            Some(c) if c == i => COL_CURR,
+                            Some(c) if c > i => COL_PAST,
+                            _ => "",
+                        };
+                        format!("{INDENT}{col}{line}{COL_END}")
+                    })
+                    .collect()
+            }
+            Lyrics::Plain(lines) => {
+                let lines = lines
+                    .iter()
+                    .flat_map(|l| {
+                        if l.is_empty() {
+                            vec![String::new()]
+                        } else {
+                            textwrap::wrap(l, width.max(1))
+                                .into_iter()
+                                .map(Into::into)
+                                .collect()
+                        }
+                    })
+                    .collect::<Vec<String>>();
+
+                // keep the scroll in bounds, now that we know how long it is
+                let mut view = self.view.lock().expect("can't lock view");
+                view.scroll =
+                    view.scroll.min(lines.len().saturating_sub(height));
+                let scroll = view.scroll;
+                drop(view);
+
+                lines
+                    .iter()
+                    .skip(scroll)
+                    .take(height)
+                    .map(|l| format!("{INDENT}{l}"))
+                    .collect()
+            }
+        }
+    }
+}
+
+// parse "mm:ss.xx", returning None for other tags like "ar:someone"
+fn parse_time(tag: &str) -> Option<Duration> {
+    let (min, sec) = tag.split_once(':')?;
+    let min: u64 = min.trim().parse().ok()?;
+    let sec: f64 = sec.trim().parse().ok()?;
+    if !(0.0..60.0).contains(&sec) {
+        return None;
+    }
+    Some(Duration::from_secs(min * 60) + Duration::from_secs_f64(sec))
+}
+
+// enhanced lrc has <mm:ss.xx> before each word, which we don't use
+fn strip_word_times(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some((before, after)) = rest.split_once('<') {
+        out.push_str(before);
+        match after.split_once('>') {
+            Some((tag, after)) if parse_time(tag).is_some() => rest = after,
+            _ => {
+                out.push('<');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.trim().into()
+}
@@ -0,0 +1,32 @@
+use super::MusicData;
+use crate::player::text;
+
+// ticks to hold still at each end before moving on
+const PAUSE: usize = 3;
+
+impl MusicData {
+    // fit a line on one row, sliding it along `speed` columns a tick if it's
+    // too long. ticks are counted from the start of the song, so every song
+    // starts from the beginning and nothing moves while paused.
+    pub(super) fn marquee(
+        &self,
+        line: &str,
+        width: usize,
+        speed: usize,
+    ) -> String {
+        let overflow = text::width(line).saturating_sub(width);
+        if overflow == 0 {
+            return line.into();
+        }
+
+        let tick = self.options.tick.as_millis().max(1);
+        let elapsed = self.elapsed().unwrap_or_default().as_millis();
+        let ticks = usize::try_from(elapsed / tick).unwrap_or_default();
+
+        // wait at the start, slide to the end, wait there, and jump back
+        let moves = overflow.div_ceil(speed);
+        let step = ticks % (PAUSE + moves + PAUSE);
+        let start = (step.saturating_sub(PAUSE) * speed).min(overflow);
+        text::slice(line, start, width)
+    }
+}
@@ -0,0 +1,113 @@
+use super::{template, MusicData};
+use crate::common::Output;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use mpd::{Client, Song};
+
+#[allow(unused_imports)]
+use debug_print::{
+    debug_eprint as deprint, debug_eprintln as deprintln,
+    debug_print as dprint, debug_println as dprintln,
+};
+
+// fields like "sticker:playcount" show a sticker instead of a tag
+pub const PREFIX: &str = "sticker:";
+
+// sticker values by file, then by name
+pub type Stickers = HashMap<String, HashMap<String, String>>;
+
+impl MusicData {
+    // fetch every sticker we show, for the whole library at once. this only
+    // needs doing at the start, and again when the database changes.
+    pub(super) fn update_stickers(&mut self, client: &Mutex<Client>) {
+        let names = self.sticker_names();
+        if names.is_empty() {
+            return;
+        }
+
+        let mut stickers = Stickers::new();
+        let mut conn = client.lock().expect("can't lock client");
+        for name in names {
+            let found =
+                conn.find_sticker("song", "", &name).unwrap_or_default();
+            dprintln!("[stickers: {} with {name}]", found.len());
+            for (file, value) in found {
+                stickers
+                    .entry(file)
+                    .or_default()
+                    .insert(name.clone(), value);
+            }
+        }
+        drop(conn);
+
+        self.stickers = Arc::new(stickers);
+        self.queue_version += 1;
+    }
+
+    // when stickers change it's nearly always the playing song's, so only
+    // those get fetched again
+    pub(super) fn update_song_stickers(&mut self, client: &Mutex<Client>) {
+        let names = self.sticker_names();
+        let file = &self.song.file;
+        if names.is_empty() || file.is_empty() {
+            return;
+        }
+
+        let mut conn = client.lock().expect("can't lock client");
+        let found = names
+            .into_iter()
+            .filter_map(|name| {
+                let value = conn.sticker("song", file, &name).ok()?;
+                Some((name, value))
+            })
+            .collect();
+        drop(conn);
+
+        Arc::make_mut(&mut self.stickers).insert(file.clone(), found);
+        self.queue_version += 1;
+    }
+
+    // a --format field for a song, which might be a sticker
+    pub(super) fn song_tag(&self, song: &Song, tag: &str) -> Option<String> {
+        if tag == "rating" {
+            let rating = self.sticker(&song.file, "rating");
+            return Some(self.fmt_rating(rating.as_deref()));
+        }
+        tag.strip_prefix(PREFIX).map_or_else(
+            || Self::get_metadata(song, tag),
+            |name| self.sticker(&song.file, name),
+        )
+    }
+
+    fn sticker(&self, file: &str, name: &str) -> Option<String> {
+        self.stickers.get(file)?.get(name).cloned()
+    }
+
+    // stickers asked for in --format or an output template
+    fn sticker_names(&self) -> Vec<String> {
+        let mut fields = self.format.clone();
+        if let Output::Oneline { template, .. }
+        | Output::I3bar { template }
+        | Output::Waybar { template } = &self.options.output
+        {
+            template::fill(template, |name| {
+                fields.push(name.into());
+                String::new()
+            });
+        }
+
+        let mut names = fields
+            .iter()
+            .filter_map(|f| match f.as_str() {
+                "rating" => Some("rating"),
+                f => f.strip_prefix(PREFIX),
+            })
+            .map(String::from)
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
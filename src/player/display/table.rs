@@ -0,0 +1,207 @@
+use super::{eta, sticker, MusicData, UNKNOWN};
+use crate::common::{Align, Column, ColumnWidth};
+use crate::player::text;
+
+use std::cmp::min;
+
+const COL_HEAD: &str = "\x1b[1;4m"; // bold underline
+const COL_CURR: &str = "\x1b[7m"; // reverse
+const COL_FOCUS: &str = "\x1b[1;4m"; // bold underline
+const COL_END: &str = "\x1b[0m"; // reset
+
+// space between columns
+const GAP: &str = "  ";
+
+impl MusicData {
+    pub(super) fn print_table(
+        &self,
+        queue_height: u32,
+        width: usize,
+    ) -> Vec<String> {
+        let tags = self.table_tags();
+        let columns = tags.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+
+        // build every cell up front, auto widths depend on all of them
+        let header = tags.iter().map(|(t, _)| heading(t)).collect::<Vec<_>>();
+        let song_pos = self.song.place.map_or_else(|| 0, |p| p.pos);
+        let focus = self.view.lock().expect("can't lock view").focus;
+        let etas = self.etas(eta::around(
+            focus.unwrap_or(song_pos as usize),
+            queue_height,
+        ));
+        let rows = self
+            .queue
+            .iter()
+            .zip(&etas)
+            .map(|(song, eta)| {
+                tags.iter()
+                    .map(|(t, _)| {
+                        let value = if *t == "eta" {
+                            eta.clone()
+                        } else {
+                            self.song_tag(song, t)
+                        };
+                        value.unwrap_or_else(|| UNKNOWN.into())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let times = self
+            .queue
+            .iter()
+            .map(|s| Self::get_pretty_time(s.duration).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        // measure the fixed parts of each line
+        let queue_size: u32 = self.queue.len().try_into().unwrap_or(0);
+        let padding = 1 + queue_size.checked_ilog10().unwrap_or_default();
+        let padding = padding as usize;
+        let time_width = times
+            .iter()
+            .map(|t| text::width(t))
+            .chain([text::width("Time")])
+            .max()
+            .unwrap_or_default();
+        let fixed = 2 + padding + GAP.len() * (columns.len() + 1) + time_width;
+        let avail = width.saturating_sub(fixed);
+
+        // work out how wide each column gets to be
+        let natural = (0..columns.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|r| text::width(&r[i]))
+                    .chain([text::width(&header[i])])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let widths = column_widths(&columns, &natural, avail);
+
+        let line = |cells: &[String], index: &str, time: &str| {
+            let cells = cells
+                .iter()
+                .zip(&widths)
+                .zip(&columns)
+                .map(|((c, w), col)| text::align(c, *w, col.align))
+                .collect::<Vec<_>>()
+                .join(GAP);
+            // the slack goes before the time, so it sits on the right edge
+            let used = 2 + padding + GAP.len() + text::width(&cells);
+            let slack = width.saturating_sub(used + GAP.len() + time_width);
+            let time = text::align(time, time_width, Align::Right);
+            text::truncate(
+                &format!(
+                    "{index:>padding$}{GAP}{cells}{}{GAP}{time}",
+                    " ".repeat(slack)
+                ),
+                width,
+            )
+        };
+
+        let mut table = vec![format!(
+            "{COL_HEAD}{}{COL_END}",
+            line(&header, &format!("{:>1$}", "#", padding + 2), "Time")
+        )];
+        let body = rows
+            .iter()
+            .zip(&times)
+            .enumerate()
+            .map(|(i, (row, time))| {
+                let is_curr = i == song_pos as usize;
+                let index = format!(
+                    "{} {:>padding$}",
+                    if is_curr { '>' } else { ' ' },
+                    i + 1
+                );
+                let line = line(row, &index, time);
+                mark(line, is_curr, focus == Some(i))
+            })
+            .collect::<Vec<_>>();
+
+        // the header row takes up one line of the queue
+        table.extend_from_slice(Self::filter_queue(
+            &body,
+            queue_height.saturating_sub(1),
+            queue_size,
+            focus.and_then(|f| f.try_into().ok()).unwrap_or(song_pos),
+        ));
+        table
+    }
+
+    // pick out the tags we're actually showing, with their column specs
+    fn table_tags(&self) -> Vec<(&String, Column)> {
+        self.format
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                self.options.verbose
+                    || !*self.verbose_tags.get(*i).unwrap_or(&false)
+            })
+            .map(|(i, tag)| {
+                (
+                    tag,
+                    self.options.columns.get(i).copied().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn column_widths(
+    columns: &[Column],
+    natural: &[usize],
+    avail: usize,
+) -> Vec<usize> {
+    let mut widths = vec![0; columns.len()];
+    let mut remaining = avail;
+
+    // fixed and percentage columns get what they ask for first
+    for (i, col) in columns.iter().enumerate() {
+        let want = match col.width {
+            ColumnWidth::Fixed(n) => n,
+            ColumnWidth::Percent(p) => avail * p / 100,
+            ColumnWidth::Auto => continue,
+        };
+        widths[i] = min(want, remaining);
+        remaining -= widths[i];
+    }
+
+    // auto columns split the rest, narrowest first, so that short columns
+    // never get cut just to leave space for long ones
+    let mut autos = (0..columns.len())
+        .filter(|i| columns[*i].width == ColumnWidth::Auto)
+        .collect::<Vec<_>>();
+    autos.sort_by_key(|i| natural[*i]);
+    let mut left = autos.len();
+    for i in autos {
+        widths[i] = min(natural[i], remaining / left);
+        remaining -= widths[i];
+        left -= 1;
+    }
+
+    widths
+}
+
+// stickers are headed by their name alone
+fn heading(tag: &str) -> String {
+    capitalize(tag.strip_prefix(sticker::PREFIX).unwrap_or(tag))
+}
+
+pub(super) fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+}
+
+// the playing row reversed, and a song that was found underlined
+fn mark(line: String, curr: bool, found: bool) -> String {
+    if !curr && !found {
+        return line;
+    }
+    format!(
+        "{}{}{line}{COL_END}",
+        if found { COL_FOCUS } else { "" },
+        if curr { COL_CURR } else { "" },
+    )
+}
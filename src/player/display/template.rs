@@ -0,0 +1,201 @@
+use super::{bar, MusicData, UNKNOWN};
+use crate::common::{Column, ColumnWidth};
+use crate::player::text;
+
+use mpd::State;
+
+// width of the progress bar when it isn't filling up the header
+const BAR_WIDTH: usize = 10;
+
+impl MusicData {
+    // look up a template field. anything we don't know about is treated as
+    // a tag of the current song, like in --format.
+    pub(super) fn field(&self, name: &str) -> String {
+        let or_unknown =
+            |s: Option<String>| s.unwrap_or_else(|| UNKNOWN.into());
+        match name {
+            "title" => Self::read_title(&self.song),
+            "artist" => or_unknown(self.artist.clone()),
+            "album" => or_unknown(self.album.clone()),
+            "date" => or_unknown(self.date.clone()),
+            "album_track" => {
+                or_unknown(self.album_track.map(|t| t.to_string()))
+            }
+            "album_total" => {
+                or_unknown(self.album_total.map(|t| t.to_string()))
+            }
+            "queue_track" => {
+                or_unknown(self.queue_track.map(|p| (p.pos + 1).to_string()))
+            }
+            "queue_total" => {
+                or_unknown(self.queue_total.map(|t| t.to_string()))
+            }
+            "state" => match self.state {
+                State::Play => "|>",
+                State::Pause => "[]",
+                State::Stop => "><",
+            }
+            .into(),
+            "elapsed" => or_unknown(Self::get_pretty_time(self.elapsed())),
+            "duration" => or_unknown(Self::get_pretty_time(self.time_total)),
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            "percent" => or_unknown(
+                self.progress().map(|p| ((p * 100.0) as u64).to_string()),
+            ),
+            "queue_time" => {
+                or_unknown(Self::get_pretty_time(Some(self.queue_time())))
+            }
+            "queue_left" => {
+                or_unknown(Self::get_pretty_time(self.queue_left()).map(|t| {
+                    if self.is_guess() {
+                        format!("~{t}")
+                    } else {
+                        t
+                    }
+                }))
+            }
+            "next" => self.next_song(),
+            "rate" => or_unknown(self.audio.map(|a| {
+                // 44100 is "44.1", 96000 is just "96"
+                let khz = format!("{:.1}", f64::from(a.rate) / 1000.0);
+                khz.trim_end_matches(".0").into()
+            })),
+            // the server says 0 bits for floating point
+            "bits" => or_unknown(self.audio.map(|a| match a.bits {
+                0 => "float".into(),
+                b => b.to_string(),
+            })),
+            "channels" => or_unknown(self.audio.map(|a| match a.chans {
+                1 => "mono".into(),
+                2 => "stereo".into(),
+                c => format!("{c} ch"),
+            })),
+            "bitrate" => or_unknown(self.bitrate.map(|b| b.to_string())),
+            "error" => self.error.clone().unwrap_or_default(),
+            "rating" => self.get_rating(),
+            "ersc" => self.get_ersc(),
+            "volume" => self.volume.to_string(),
+            "xfade" => self
+                .crossfade
+                .map_or_else(String::new, |t| format!(" ({})", t.as_secs())),
+            "bar" => bar::render(self.options.bar, BAR_WIDTH, self.progress()),
+            _ => or_unknown(self.song_tag(&self.song, name)),
+        }
+    }
+
+    // single mode without repeat stops after this song, though the server
+    // still says what's next
+    pub(super) fn stops_after_this(&self) -> bool {
+        let flag = |i| *self.ersc_opts.get(i).unwrap_or(&false);
+        flag(2) && !flag(0) && self.queue_track.is_some()
+    }
+
+    // what the server will play after this, as "title * artist"
+    pub(super) fn next_song(&self) -> String {
+        let flag = |i| *self.ersc_opts.get(i).unwrap_or(&false);
+        let (repeat, random) = (flag(0), flag(1));
+        let curr = self.queue_track.map(|p| p.pos);
+
+        if self.stops_after_this() {
+            return "(stop after this)".into();
+        }
+        // going back to the start (or round again) is repeat, though only
+        // when the queue isn't shuffled, since then anything can be next
+        let wraps = |next: u32| {
+            curr.is_some_and(|c| next == c || (!random && next < c))
+        };
+
+        match self.queue_next {
+            Some(next) if repeat && wraps(next.pos) => "(repeat)".into(),
+            Some(next) => self.queue.get(next.pos as usize).map_or_else(
+                || UNKNOWN.into(),
+                |song| {
+                    let artist = song.artist.as_deref().unwrap_or(UNKNOWN);
+                    format!("{} * {artist}", Self::read_title(song))
+                },
+            ),
+            None => "(end of queue)".into(),
+        }
+    }
+}
+
+// fill in the "{name}" fields in a template. fields can be given a width
+// and alignment like table columns, e.g. "{volume:>3}".
+pub fn fill(template: &str, mut field: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some((before, after)) = rest.split_once('{') {
+        out.push_str(before);
+        let Some((spec, after)) = after.split_once('}') else {
+            rest = after;
+            out.push('{');
+            continue;
+        };
+
+        // the width goes after the last colon, since names like
+        // "sticker:playcount" have one of their own
+        let (name, column) = spec
+            .rsplit_once(':')
+            .and_then(|(n, c)| Some((n, Some(c.parse::<Column>().ok()?))))
+            .unwrap_or((spec, None));
+        let value = field(name);
+        match column.map(|c| (c.width, c.align)) {
+            Some((ColumnWidth::Fixed(w), align)) => {
+                out.push_str(&text::align(&value, w, align));
+            }
+            _ => out.push_str(&value),
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fill;
+
+    // every field filled in with its own name in brackets
+    fn names(template: &str) -> String {
+        fill(template, |name| format!("[{name}]"))
+    }
+
+    #[test]
+    fn fills_fields() {
+        assert_eq!(names("{title} * {artist}"), "[title] * [artist]");
+        assert_eq!(names("no fields"), "no fields");
+        assert_eq!(names(""), "");
+    }
+
+    #[test]
+    fn names_can_have_colons() {
+        assert_eq!(names("{sticker:playcount}"), "[sticker:playcount]");
+        assert_eq!(names("{a:b:c}"), "[a:b:c]");
+    }
+
+    #[test]
+    fn widths_go_after_the_last_colon() {
+        assert_eq!(fill("{volume:>3}%", |_| "7".into()), "  7%");
+        assert_eq!(fill("{volume:4}|", |_| "7".into()), "7   |");
+        assert_eq!(
+            fill("{sticker:playcount:^5}", |name| {
+                assert_eq!(name, "sticker:playcount");
+                "12".into()
+            }),
+            " 12  "
+        );
+        // only fixed widths pad, there's nothing to share out
+        assert_eq!(fill("{title:auto}|{title:50%}", |_| "x".into()), "x|x");
+    }
+
+    #[test]
+    fn long_values_are_truncated() {
+        assert_eq!(fill("{title:5}", |_| "Everything".into()), "Ever…");
+    }
+
+    #[test]
+    fn unclosed_braces_are_kept() {
+        assert_eq!(names("{title} {oops"), "[title] {oops");
+        assert_eq!(names("}{}"), "}[]");
+    }
+}
@@ -0,0 +1,308 @@
+// finding songs in the queue by what their rows say, as you type. the
+// display fills in the rows, since it's what knows how they look, and the
+// input thread moves between the matches.
+
+#[derive(Debug, Default)]
+pub struct Find {
+    pub query: String,
+    // which match is selected
+    pub selected: usize,
+    // queue rows as plain text, and the version of the queue they're from
+    rows: Vec<String>,
+    version: Option<u64>,
+    // queue positions that match, with which characters of their rows did
+    pub matches: Vec<(usize, Vec<usize>)>,
+    // what the matches are for, so typing more only looks through them
+    matched: Option<(u64, String)>,
+    // where to start, once there are matches to start on
+    from: Option<usize>,
+}
+
+impl Find {
+    // a new search, starting from a queue position
+    pub fn start(&mut self, pos: usize) {
+        self.query.clear();
+        self.matched = None;
+        self.from = Some(pos);
+    }
+
+    pub fn needs_rows(&self, version: u64) -> bool {
+        self.version != Some(version)
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<String>, version: u64) {
+        self.rows = rows;
+        self.version = Some(version);
+    }
+
+    pub fn row(&self, pos: usize) -> Option<&str> {
+        self.rows.get(pos).map(String::as_str)
+    }
+
+    pub const fn total(&self) -> usize {
+        self.rows.len()
+    }
+
+    // bring the matches up to date with the query, doing as little as we
+    // can, since this runs on every redraw of queues thousands of songs long
+    pub fn search(&mut self) {
+        let Some(version) = self.version else {
+            return;
+        };
+        let query = self.query.chars().map(lower).collect::<Vec<_>>();
+        let from = self.from.take().or_else(|| self.position());
+        let narrower = match &self.matched {
+            Some((v, q)) if *v == version && *q == self.query => return,
+            Some((v, q)) => *v == version && self.query.starts_with(q.as_str()),
+            None => false,
+        };
+
+        self.matches = if narrower {
+            self.matches
+                .iter()
+                .filter_map(|(pos, _)| {
+                    fuzzy(&query, self.rows.get(*pos)?).map(|m| (*pos, m))
+                })
+                .collect()
+        } else {
+            self.rows
+                .iter()
+                .enumerate()
+                .filter_map(|(pos, row)| fuzzy(&query, row).map(|m| (pos, m)))
+                .collect()
+        };
+        self.matched = Some((version, self.query.clone()));
+
+        // stay on the same song, or the next one that still matches
+        if let Some(pos) = from {
+            self.select_from(pos);
+        }
+    }
+
+    // the queue position of the selected match
+    pub fn position(&self) -> Option<usize> {
+        self.matches.get(self.selected).map(|(pos, _)| *pos)
+    }
+
+    // start on a queue position, or the first match after it
+    pub fn select_from(&mut self, pos: usize) {
+        self.selected = self.matches.partition_point(|(p, _)| *p < pos);
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+    }
+
+    pub fn move_by(&mut self, by: isize) {
+        let last = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(by).min(last);
+    }
+
+    // the next match down (or up, if negative), going round at the ends
+    pub fn select_by(&mut self, by: isize) {
+        let len = self.matches.len();
+        if len == 0 {
+            return;
+        }
+        let by = by.rem_euclid(len.try_into().unwrap_or(isize::MAX));
+        self.selected = (self.selected + by.unsigned_abs()) % len;
+    }
+}
+
+// where each character of the query is in a row, ignoring case. they're
+// looked for together first, then spread out, left to right.
+fn fuzzy(query: &[char], row: &str) -> Option<Vec<usize>> {
+    if query.is_empty() {
+        return Some(Vec::new());
+    }
+    let row = row.chars().map(lower).collect::<Vec<_>>();
+
+    if let Some(start) = row.windows(query.len()).position(|w| w == query) {
+        return Some((start..start + query.len()).collect());
+    }
+
+    let mut found = Vec::with_capacity(query.len());
+    let mut chars = row.iter().enumerate();
+    for q in query {
+        let (i, _) = chars.find(|(_, c)| *c == q)?;
+        found.push(i);
+    }
+    Some(found)
+}
+
+// one character for one character, so positions still line up
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUEUE: [&str; 5] = [
+        "Everything in Its Right Place  *  Radiohead",
+        "Kid A  *  Radiohead",
+        "All I Want  *  Joni Mitchell",
+        "Carey  *  Joni Mitchell",
+        "Blue  *  Joni Mitchell",
+    ];
+
+    fn find(query: &str, from: usize) -> Find {
+        let mut find = Find::default();
+        find.set_rows(QUEUE.iter().map(ToString::to_string).collect(), 0);
+        find.start(from);
+        find.query = query.into();
+        find.search();
+        find
+    }
+
+    fn positions(find: &Find) -> Vec<usize> {
+        find.matches.iter().map(|(pos, _)| *pos).collect()
+    }
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn fuzzy_prefers_letters_together() {
+        // "ue" is in "Blue", though a 'u' and an 'e' come sooner apart
+        assert_eq!(fuzzy(&chars("ue"), "Sure, Blue"), Some(vec![8, 9]));
+        assert_eq!(fuzzy(&chars("ue"), "Blue"), Some(vec![2, 3]));
+    }
+
+    #[test]
+    fn fuzzy_spreads_out_in_order() {
+        assert_eq!(fuzzy(&chars("jm"), "Joni Mitchell"), Some(vec![0, 5]));
+        assert_eq!(fuzzy(&chars("mj"), "Joni Mitchell"), None);
+        assert_eq!(fuzzy(&chars("xyz"), "Joni Mitchell"), None);
+    }
+
+    #[test]
+    fn fuzzy_ignores_case() {
+        // the query is lowered once by search, not for every row
+        assert_eq!(fuzzy(&chars("kid"), "KID A"), Some(vec![0, 1, 2]));
+        assert_eq!(fuzzy(&chars("ü"), "Über"), Some(vec![0]));
+        assert_eq!(positions(&find("KID", 0)), [1]);
+    }
+
+    #[test]
+    fn fuzzy_counts_chars_not_bytes() {
+        assert_eq!(fuzzy(&chars("京"), "東京 Tokyo"), Some(vec![1]));
+        assert_eq!(fuzzy(&chars("ty"), "東京 Tokyo"), Some(vec![3, 6]));
+    }
+
+    #[test]
+    fn fuzzy_matches_everything_with_nothing() {
+        assert_eq!(fuzzy(&[], "anything"), Some(Vec::new()));
+        assert_eq!(fuzzy(&[], ""), Some(Vec::new()));
+        assert_eq!(fuzzy(&chars("a"), ""), None);
+    }
+
+    #[test]
+    fn an_empty_query_matches_the_whole_queue() {
+        assert_eq!(positions(&find("", 0)), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn typing_more_narrows_like_starting_again() {
+        let mut narrowed = find("", 0);
+        for c in "joni".chars() {
+            narrowed.query.push(c);
+            narrowed.search();
+        }
+        let fresh = find("joni", 0);
+        assert_eq!(narrowed.matches, fresh.matches);
+        assert_eq!(positions(&narrowed), [2, 3, 4]);
+    }
+
+    #[test]
+    fn deleting_widens_again() {
+        let mut find = find("carey", 0);
+        assert_eq!(positions(&find), [3]);
+        find.query.truncate(1);
+        find.search();
+        // "c" is in Carey, Mitchell and Place
+        assert_eq!(positions(&find), [0, 2, 3, 4]);
+    }
+
+    #[test]
+    fn starts_on_the_first_match_from_where_we_were() {
+        assert_eq!(find("joni", 3).position(), Some(3));
+        assert_eq!(find("radiohead", 2).position(), Some(1));
+        assert_eq!(find("joni", 0).position(), Some(2));
+        // nothing after it, so the last one
+        assert_eq!(find("radiohead", 4).position(), Some(1));
+    }
+
+    #[test]
+    fn stays_on_the_same_song_while_it_matches() {
+        let mut find = find("", 3);
+        assert_eq!(find.position(), Some(3));
+        find.query = "i".into();
+        find.search();
+        assert_eq!(find.position(), Some(3));
+        find.query.clear();
+        find.search();
+        assert_eq!(find.position(), Some(3));
+        // only Blue has a 'u', so Carey is gone and it's the next one down
+        find.query = "u".into();
+        find.search();
+        assert_eq!(positions(&find), [4]);
+        assert_eq!(find.position(), Some(4));
+    }
+
+    #[test]
+    fn waits_for_rows_before_starting() {
+        let mut find = Find::default();
+        find.start(3);
+        find.query = "joni".into();
+        find.search();
+        assert_eq!(find.position(), None);
+
+        // the display fills them in later, and we still start where we were
+        find.set_rows(QUEUE.iter().map(ToString::to_string).collect(), 0);
+        find.search();
+        assert_eq!(find.position(), Some(3));
+    }
+
+    #[test]
+    fn a_new_queue_is_searched_again() {
+        let mut find = find("blue", 0);
+        assert_eq!(positions(&find), [4]);
+        assert!(!find.needs_rows(0));
+        assert!(find.needs_rows(1));
+
+        find.set_rows(vec!["Blue".into(), "Green".into(), "Blue".into()], 1);
+        find.search();
+        assert_eq!(positions(&find), [0, 2]);
+    }
+
+    #[test]
+    fn next_and_previous_go_round() {
+        let mut find = find("joni", 2);
+        find.select_by(1);
+        assert_eq!(find.position(), Some(3));
+        find.select_by(2);
+        assert_eq!(find.position(), Some(2));
+        find.select_by(-1);
+        assert_eq!(find.position(), Some(4));
+        find.select_by(-7);
+        assert_eq!(find.position(), Some(3));
+    }
+
+    #[test]
+    fn scrolling_stops_at_the_ends() {
+        let mut find = find("joni", 2);
+        find.move_by(-5);
+        assert_eq!(find.position(), Some(2));
+        find.move_by(10);
+        assert_eq!(find.position(), Some(4));
+    }
+
+    #[test]
+    fn nothing_to_select_without_matches() {
+        let mut find = find("zzz", 0);
+        assert!(find.matches.is_empty());
+        find.select_by(1);
+        find.move_by(1);
+        assert_eq!(find.position(), None);
+    }
+}
@@ -1,14 +1,23 @@
 use crate::common;
-use crate::common::ExitCode;
+use crate::common::{Action, ExitCode, Key};
+
+use super::browse;
+use super::browse::playlists::{self, Prompt};
+use super::browse::search::{self, Job};
+use super::browse::{files, AddMode, Browser, Item};
+use super::keymap::Keymap;
+use super::refresh;
+use super::view::{HelpKey, SharedView, View};
 
 use std::io;
-use std::io::{Read, Write};
-use std::sync::{Arc, Mutex};
+use std::io::{BufRead, Read, Write};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use mpd::{message::Channel, Client, State};
-use termios::{tcsetattr, Termios, ECHO, ICANON, TCSANOW};
+use termios::{tcsetattr, Termios, ECHO, ICANON, TCSANOW, VMIN, VTIME};
 use uuid::Uuid;
 
 #[allow(unused_imports)]
@@ -19,15 +28,29 @@ use debug_print::{
 
 pub struct KeyHandler {
     client: Arc<Mutex<Client>>,
+    address: String,
     uuid: Uuid,
+    view: SharedView,
+    keymap: Arc<Keymap>,
+    searches: OnceLock<Sender<Job>>,
 }
 
 impl KeyHandler {
     #[must_use]
-    pub fn new(client: Client, uuid: Uuid) -> Self {
+    pub fn new(
+        client: Client,
+        address: &str,
+        uuid: Uuid,
+        view: SharedView,
+        keymap: Arc<Keymap>,
+    ) -> Self {
         Self {
             client: Arc::new(Mutex::new(client)),
+            address: address.into(),
             uuid,
+            view,
+            keymap,
+            searches: OnceLock::new(),
         }
     }
 
@@ -47,36 +70,97 @@ impl KeyHandler {
         });
 
         loop {
-            let ch = getch().unwrap_or_default();
-            // returns "quit"
+            let Ok(key) = getkey() else {
+                continue;
+            };
             let mut conn = self.client.lock().unwrap_or_else(|_| {
                 common::stop_ansi();
                 common::clean_exit(ExitCode::Error);
             });
-            if self.handle_key(ch, &mut conn) {
-                break;
+
+            // help, search, finding and playlist prompts take typing, so
+            // they get keys before the keymap does
+            let view = self.view.lock().expect("can't lock view");
+            let (current, typing) = (view.view, view.search.typing);
+            let asking = view.playlists.prompt.is_some();
+            drop(view);
+            if current == View::Help {
+                if self.handle_help(key, &mut conn) {
+                    break;
+                }
+                continue;
+            }
+            if current == View::Find {
+                self.handle_find(key, &mut conn);
+                continue;
             }
+            if current == View::Search && typing {
+                self.handle_search(key, &mut conn);
+                continue;
+            }
+            if current == View::Playlists && asking {
+                self.handle_prompt(key, &mut conn);
+                continue;
+            }
+
+            match self.keymap.get(key) {
+                // returns "quit"
+                Some(action) => {
+                    if self.handle_action(action, &mut conn) {
+                        break;
+                    }
+                }
+                None => {
+                    #[cfg(debug_assertions)]
+                    println!("getkey(): {key:?}");
+                }
+            }
+        }
+    }
+
+    // do one thing, for things that aren't a keyboard
+    pub fn click(&self, action: Action) {
+        let mut conn =
+            self.client.lock().expect("can't get command connection");
+        self.handle_action(action, &mut conn);
+    }
+
+    // i3bar sends clicks on stdin, as an endless json array
+    pub fn read_clicks(&self) {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            // each event is on its own line, maybe after a '[' or ','
+            let event = line.trim_start_matches(['[', ',']);
+            let Ok(event) = serde_json::from_str::<serde_json::Value>(event)
+            else {
+                continue;
+            };
+            let click = match event["button"].as_u64() {
+                Some(1) => Action::Toggle,
+                Some(3) => Action::RateUp,
+                Some(4) => Action::VolumeUp,
+                Some(5) => Action::VolumeDown,
+                _ => continue,
+            };
+            dprintln!("input: {click:?}");
+            self.click(click);
         }
     }
 
     // huge match statement to handle keyboard input. returns "quit" param.
-    // TODO: make this return an enum
     #[allow(clippy::too_many_lines)]
-    fn handle_key(&self, ch: char, conn: &mut Client) -> bool {
-        match ch {
+    fn handle_action(&self, action: Action, conn: &mut Client) -> bool {
+        match action {
             // helptext
-            'h' | '?' | '/' => {
-                return self.handle_help(conn);
+            Action::Help => {
+                self.view
+                    .lock()
+                    .expect("can't lock view")
+                    .toggle(View::Help);
+                refresh(conn);
             }
             // quit
-            'q' | 'Q' => {
-                let _ = conn.unsubscribe(
-                    Channel::new(
-                        format!("help_{}", self.uuid.simple()).as_str(),
-                    )
-                    .expect("can't make help channel"),
-                );
-
+            Action::Quit => {
                 dprintln!("input: quitting!");
                 let _ = conn.subscribe(
                     Channel::new(
@@ -87,8 +171,8 @@ impl KeyHandler {
                 return true;
             }
 
-            // space for pause/play
-            ' ' => {
+            // pause/play
+            Action::Toggle => {
                 let state = conn.status().unwrap_or_default().state;
                 match state {
                     State::Play => {
@@ -101,21 +185,21 @@ impl KeyHandler {
             }
 
             // prev
-            'p' | 'k' => {
+            Action::Prev => {
                 let _ = conn.prev();
             }
             // next
-            'n' | 'j' => {
+            Action::Next => {
                 let _ = conn.next();
             }
             // volume up
-            '=' | '+' | '0' | ')' => {
+            Action::VolumeUp => {
                 let vol = conn.status().unwrap_or_default().volume;
                 let vol = std::cmp::min(100, vol + 5);
                 let _ = conn.volume(vol);
             }
             // volume down
-            '-' | '_' | '9' | '(' => {
+            Action::VolumeDown => {
                 let vol = conn.status().unwrap_or_default().volume;
                 // volume is i8, so you can do this
                 let vol = std::cmp::max(0, vol - 5);
@@ -123,21 +207,17 @@ impl KeyHandler {
             }
 
             // seek backwards
-            'H' => {
+            Action::SeekBack => {
                 let time = conn
                     .status()
                     .unwrap_or_default()
                     .elapsed
                     .unwrap_or_default();
-                let time = if time.as_secs() <= 10 {
-                    Duration::from_secs(0)
-                } else {
-                    time - Duration::from_secs(10)
-                };
+                let time = time.saturating_sub(Duration::from_secs(10));
                 let _ = conn.rewind(time);
             }
             // seek forwards
-            'L' => {
+            Action::SeekAhead => {
                 let time = conn
                     .status()
                     .unwrap_or_default()
@@ -148,41 +228,41 @@ impl KeyHandler {
             }
 
             // ratings
-            '{' => {
+            Action::RateDown => {
                 Self::inc_rating(-1, conn);
             }
-            '}' => {
+            Action::RateUp => {
                 Self::inc_rating(1, conn);
             }
 
             // repeat
-            'E' => {
+            Action::Repeat => {
                 let state = conn.status().unwrap_or_default().repeat;
                 let _ = conn.repeat(!state);
             }
             // random
-            'R' => {
+            Action::Random => {
                 let state = conn.status().unwrap_or_default().random;
                 let _ = conn.random(!state);
             }
             // single
-            'S' => {
+            Action::Single => {
                 let state = conn.status().unwrap_or_default().single;
                 let _ = conn.single(!state);
             }
             // consume
-            'C' => {
+            Action::Consume => {
                 let state = conn.status().unwrap_or_default().consume;
                 let _ = conn.consume(!state);
             }
 
             // shuffle
-            'F' => {
+            Action::Shuffle => {
                 let _ = conn.shuffle(..);
             }
 
             // crossfade up
-            'x' => {
+            Action::CrossfadeUp => {
                 let crossfade = conn
                     .status()
                     .unwrap_or_default()
@@ -193,72 +273,538 @@ impl KeyHandler {
             }
 
             // crossfade down
-            'X' => {
+            Action::CrossfadeDown => {
                 let crossfade = conn
                     .status()
                     .unwrap_or_default()
                     .crossfade
                     .unwrap_or_default();
                 if crossfade.as_secs() != 0 {
-                    let crossfade = crossfade - Duration::from_secs(1);
+                    let crossfade =
+                        crossfade.saturating_sub(Duration::from_secs(1));
                     let _ = conn.crossfade(crossfade);
                 }
             }
 
             // stop
-            'M' => {
+            Action::Stop => {
                 let _ = conn.stop();
             }
 
-            // default
-            _ => {
-                #[cfg(debug_assertions)]
-                println!("getch(): {ch}");
+            // clear playback error
+            Action::ClearError => {
+                let _ = conn.clearerror();
+            }
+
+            // lyrics
+            Action::Lyrics => {
+                self.view
+                    .lock()
+                    .expect("can't lock view")
+                    .toggle(View::Lyrics);
+                refresh(conn);
+            }
+            // the display says where we are, if it's talking
+            Action::WhereAmI => {
+                self.view.lock().expect("can't lock view").where_am_i = true;
+                refresh(conn);
             }
+            // next layout, which the display shrinks to fit
+            Action::Layout => {
+                self.view.lock().expect("can't lock view").next_layout();
+                refresh(conn);
+            }
+            // scroll down/up, the display keeps this in bounds
+            Action::ScrollDown => {
+                self.view.lock().expect("can't lock view").scroll_by(1);
+                refresh(conn);
+            }
+            Action::ScrollUp => {
+                self.view.lock().expect("can't lock view").scroll_by(-1);
+                refresh(conn);
+            }
+            Action::PageDown => {
+                self.view.lock().expect("can't lock view").scroll_pages(1);
+                refresh(conn);
+            }
+            Action::PageUp => {
+                self.view.lock().expect("can't lock view").scroll_pages(-1);
+                refresh(conn);
+            }
+            // collapse/expand album group
+            Action::Collapse => {
+                self.view.lock().expect("can't lock view").collapse = true;
+                refresh(conn);
+            }
+            // library, loaded the first time it's opened
+            Action::Library => {
+                let mut view = self.view.lock().expect("can't lock view");
+                view.toggle(View::Library);
+                if view.view == View::Library && view.library.is_empty() {
+                    drop(view);
+                    let level = browse::library::artists(conn);
+                    let mut view = self.view.lock().expect("can't lock view");
+                    match level {
+                        Ok(level) => view.library.push(level),
+                        Err(e) => view.library.message = Some(e),
+                    }
+                }
+                refresh(conn);
+            }
+            // the music directory, loaded the first time it's opened
+            Action::Files => {
+                let mut view = self.view.lock().expect("can't lock view");
+                view.toggle(View::Files);
+                if view.view == View::Files && view.files.is_empty() {
+                    drop(view);
+                    let level = files::list(&self.address, "");
+                    let mut view = self.view.lock().expect("can't lock view");
+                    match level {
+                        Ok(level) => view.files.push(level),
+                        Err(e) => view.files.message = Some(e),
+                    }
+                }
+                refresh(conn);
+            }
+            // the playing song's directory, from wherever we are
+            Action::Locate => {
+                let file = conn
+                    .currentsong()
+                    .ok()
+                    .flatten()
+                    .map(|song| song.file)
+                    .unwrap_or_default();
+                let levels = files::locate(&self.address, &file);
+
+                let mut view = self.view.lock().expect("can't lock view");
+                if view.view != View::Files {
+                    view.toggle(View::Files);
+                }
+                match levels {
+                    Ok(levels) => {
+                        view.files = Browser::default();
+                        for level in levels {
+                            view.files.push(level);
+                        }
+                    }
+                    Err(e) => view.files.message = Some(e),
+                }
+                drop(view);
+                refresh(conn);
+            }
+            // stored playlists, loaded the first time they're opened. the
+            // display keeps them up to date after that.
+            Action::Playlists => {
+                let mut view = self.view.lock().expect("can't lock view");
+                view.toggle(View::Playlists);
+                if view.view == View::Playlists
+                    && view.playlists.browser.is_empty()
+                {
+                    drop(view);
+                    self.load_playlists(conn);
+                }
+                refresh(conn);
+            }
+            // ask for a name, from wherever we are
+            Action::SavePlaylist => {
+                let mut view = self.view.lock().expect("can't lock view");
+                if view.view != View::Playlists {
+                    view.toggle(View::Playlists);
+                }
+                view.playlists.ask(Prompt::Save);
+                let empty = view.playlists.browser.is_empty();
+                drop(view);
+                if empty {
+                    self.load_playlists(conn);
+                }
+                refresh(conn);
+            }
+            Action::Rename
+            | Action::Remove
+            | Action::MoveUp
+            | Action::MoveDown => self.edit_playlist(action, conn),
+            // search, starting with the prompt
+            Action::Search => {
+                let mut view = self.view.lock().expect("can't lock view");
+                view.toggle(View::Search);
+                view.search.typing = true;
+                drop(view);
+                refresh(conn);
+            }
+            // go in and out of lists, or close whatever's open
+            Action::Open => self.open(conn),
+            Action::Back => {
+                let mut view = self.view.lock().expect("can't lock view");
+                let popped = view.browser_mut().is_some_and(Browser::pop);
+                if !popped && view.view == View::Search {
+                    view.search.typing = true;
+                } else if !popped && view.view != View::Queue {
+                    let current = view.view;
+                    view.toggle(current);
+                } else if view.view == View::Queue {
+                    view.focus = None;
+                }
+                drop(view);
+                refresh(conn);
+            }
+            // find a song in the queue, starting from the one playing
+            Action::Find => {
+                let pos = conn.status().ok().and_then(|s| s.song);
+                let mut view = self.view.lock().expect("can't lock view");
+                view.toggle(View::Find);
+                view.find.start(pos.map_or(0, |p| p.pos as usize));
+                drop(view);
+                refresh(conn);
+            }
+            // show the next song found, in the queue
+            Action::NextMatch => self.next_match(1, conn),
+            Action::PrevMatch => self.next_match(-1, conn),
+            // put the selection in the queue
+            Action::Add => self.add(AddMode::Append, false, conn),
+            Action::AddAll => self.add(AddMode::Append, true, conn),
+            Action::Insert => self.add(AddMode::Insert, false, conn),
+            Action::Replace => self.add(AddMode::Replace, false, conn),
         }
         false
     }
 
-    fn handle_help(&self, conn: &mut Client) -> bool {
-        // make help channel
-        let help_chan =
-            Channel::new(format!("help_{}", self.uuid.simple()).as_str())
-                .expect("can't make help channel");
+    fn open(&self, conn: &mut Client) {
+        let mut view = self.view.lock().expect("can't lock view");
+        // in the queue, that's the song that was found
+        if view.view == View::Queue {
+            let focus = view.focus.take();
+            drop(view);
+            if let Some(pos) = focus {
+                Self::play(pos, conn);
+            }
+            return;
+        }
+        let Some(item) = view.browser().and_then(|b| b.selected()).cloned()
+        else {
+            return;
+        };
+        drop(view);
+
+        let level = browse::open(conn, &self.address, &item);
+        let mut view = self.view.lock().expect("can't lock view");
+        if let Some(browser) = view.browser_mut() {
+            match level {
+                Ok(Some(level)) => browser.push(level),
+                Ok(None) => {}
+                Err(e) => browser.message = Some(e),
+            }
+        }
+        drop(view);
+        refresh(conn);
+    }
+
+    // the selection, or everything in the list
+    fn add(&self, mode: AddMode, all: bool, conn: &mut Client) {
+        let view = self.view.lock().expect("can't lock view");
+        let items = view.browser().and_then(Browser::level).map(|level| {
+            if all {
+                level.items.clone()
+            } else {
+                level.items.get(level.cursor).cloned().into_iter().collect()
+            }
+        });
+        drop(view);
+        let Some(items) = items.filter(|i| !i.is_empty()) else {
+            return;
+        };
+
+        let message = match browse::add(conn, &items, mode) {
+            Ok(n) => {
+                let songs = if n == 1 { "song" } else { "songs" };
+                match mode {
+                    AddMode::Append => format!("added {n} {songs}"),
+                    AddMode::Insert => format!("inserted {n} {songs}"),
+                    AddMode::Replace => format!("playing {n} {songs}"),
+                }
+            }
+            Err(e) => e,
+        };
+        let mut view = self.view.lock().expect("can't lock view");
+        if let Some(browser) = view.browser_mut() {
+            browser.message = Some(message);
+        }
+        drop(view);
+        refresh(conn);
+    }
+
+    // keys while the help is up: typing narrows it down, and the usual
+    // keys scroll it
+    // returns "quit", since quitting works from here too. once a filter is
+    // being typed, the help keys are just letters, and esc stops typing.
+    fn handle_help(&self, key: Key, conn: &mut Client) -> bool {
+        let action = self.keymap.get(key);
+        let done = self
+            .view
+            .lock()
+            .expect("can't lock view")
+            .help_key(key, action);
+        match done {
+            HelpKey::Quit => self.handle_action(Action::Quit, conn),
+            HelpKey::Changed => {
+                refresh(conn);
+                false
+            }
+            HelpKey::Ignored => false,
+        }
+    }
 
-        // if help is in channels, unsubscribe
-        if conn.channels().unwrap_or_default().contains(&help_chan) {
-            dprintln!("input: -help_chan");
-            let _ = conn.unsubscribe(help_chan);
+    fn load_playlists(&self, conn: &mut Client) {
+        let fetched = playlists::fetch(conn, None);
+        let mut view = self.view.lock().expect("can't lock view");
+        match fetched {
+            Ok(fetched) => view.playlists.update(fetched),
+            Err(e) => view.playlists.browser.message = Some(e),
+        }
+        drop(view);
+        playlists::summarize_later(
+            SharedView::clone(&self.view),
+            self.address.clone(),
+        );
+    }
 
-            // toggle temp channel to force idle break
-            let _ = conn.subscribe(
-                Channel::new("tmp").expect("can't make temp channel"),
-            );
-            let _ = conn.unsubscribe(
-                Channel::new("tmp").expect("can't make temp channel"),
-            );
+    // change the playlist that's selected, or the one we're in. anything
+    // that can't be undone asks first.
+    fn edit_playlist(&self, action: Action, conn: &mut Client) {
+        let mut view = self.view.lock().expect("can't lock view");
+        if view.view != View::Playlists {
+            return;
+        }
+        let lists = &mut view.playlists;
+        let open = lists.open().map(ToString::to_string);
+        let (cursor, len) = lists
+            .browser
+            .level()
+            .map_or((0, 0), |l| (l.cursor, l.items.len()));
+        let selected = match lists.browser.selected() {
+            Some(Item::Playlist(name)) => Some(name.clone()),
+            _ => None,
+        };
+
+        let (name, by) = match (action, open) {
+            (Action::Rename, open) => {
+                if let Some(name) = open.or(selected) {
+                    lists.ask(Prompt::Rename(name));
+                }
+                (None, 0)
+            }
+            (Action::Remove, None) => {
+                if let Some(name) = selected {
+                    lists.ask(Prompt::Delete(name));
+                }
+                (None, 0)
+            }
+            (Action::Remove, Some(name)) if cursor < len => (Some(name), 0),
+            (Action::MoveUp, Some(name)) if cursor > 0 => (Some(name), -1),
+            (Action::MoveDown, Some(name)) if cursor + 1 < len => {
+                (Some(name), 1)
+            }
+            _ => (None, 0),
+        };
+        drop(view);
+
+        // songs in a playlist change right away, and the cursor follows
+        if let Some(name) = name {
+            let result = if by == 0 {
+                playlists::remove(conn, &name, cursor)
+            } else {
+                playlists::shift(conn, &name, cursor, by)
+            };
+            let mut view = self.view.lock().expect("can't lock view");
+            match result {
+                Ok(()) => view.playlists.browser.move_by(by),
+                Err(e) => view.playlists.browser.message = Some(e),
+            }
+        }
+        refresh(conn);
+    }
 
-        // otherwise, subscribe to help channel and make a fake getch() loop
-        } else {
-            dprintln!("input: +help_chan");
-            let _ = conn.subscribe(help_chan);
-
-            // use our own getch() loop, to prevent sending commands to the server during helptext
-            loop {
-                let ch = getch().unwrap_or_default();
-                // allow esc to close helptext by remapping it
-                let ch = if ch == '\x1b' { '?' } else { ch };
-                match ch {
-                    // allowed inputs during helptext: esc, help, quit
-                    'h' | '?' | '/' | 'q' | 'Q' => {
-                        return self.handle_key(ch, conn);
-                    }
-                    _ => {}
+    // keys while a playlist prompt is up: typing fills it in, enter does
+    // it, and esc changes our mind. deleting only takes y or n.
+    fn handle_prompt(&self, key: Key, conn: &mut Client) {
+        let mut view = self.view.lock().expect("can't lock view");
+        let deleting = matches!(view.playlists.prompt, Some(Prompt::Delete(_)));
+        match key {
+            Key::Esc => view.playlists.prompt = None,
+            // deleting is yes or no, there's nothing to type
+            Key::Char('y' | 'Y') if deleting => {
+                drop(view);
+                return self.answer_prompt(conn);
+            }
+            Key::Char('n' | 'N') if deleting => view.playlists.prompt = None,
+            _ if deleting => return,
+            Key::Enter => {
+                drop(view);
+                return self.answer_prompt(conn);
+            }
+            Key::Backspace => {
+                view.playlists.typed.pop();
+            }
+            Key::Char(c) if !c.is_control() => view.playlists.typed.push(c),
+            _ => return,
+        }
+        drop(view);
+        refresh(conn);
+    }
+
+    fn answer_prompt(&self, conn: &mut Client) {
+        let view = self.view.lock().expect("can't lock view");
+        let Some(prompt) = view.playlists.prompt.clone() else {
+            return;
+        };
+        let typed = view.playlists.typed.clone();
+        drop(view);
+        let result = playlists::answer(conn, &prompt, &typed);
+
+        // a bad name can be fixed, so the prompt stays for errors
+        let mut view = self.view.lock().expect("can't lock view");
+        let message = match result {
+            Ok(message) => {
+                // the list gets sorted again, so keep track of it
+                if let Prompt::Rename(_) | Prompt::Save = prompt {
+                    view.playlists.follow = Some(typed.trim().to_string());
                 }
+                view.playlists.prompt = None;
+                message
             }
+            Err(e) => e,
+        };
+        view.playlists.browser.message = Some(message);
+        drop(view);
+        refresh(conn);
+    }
+
+    fn next_match(&self, by: isize, conn: &mut Client) {
+        let mut view = self.view.lock().expect("can't lock view");
+        if view.view != View::Queue {
+            return;
         }
+        view.find.search();
+        view.find.select_by(by);
+        view.focus = view.find.position();
+        drop(view);
+        refresh(conn);
+    }
 
-        false
+    // keys while finding: typing narrows the queue down, and the usual
+    // keys move between what's left. the display fills in the rows to
+    // look through, and everything here looks again before using them,
+    // since typing can be quicker than drawing.
+    fn handle_find(&self, key: Key, conn: &mut Client) {
+        let mut view = self.view.lock().expect("can't lock view");
+        match key {
+            Key::Esc => view.toggle(View::Find),
+            // play it
+            Key::Enter => {
+                view.find.search();
+                let pos = view.find.position();
+                view.toggle(View::Find);
+                view.focus = None;
+                drop(view);
+                if let Some(pos) = pos {
+                    Self::play(pos, conn);
+                }
+                return;
+            }
+            Key::Backspace => {
+                view.find.query.pop();
+                view.find.search();
+            }
+            Key::Char(c) if !c.is_control() => {
+                view.find.query.push(c);
+                view.find.search();
+            }
+            _ => match self.keymap.get(key) {
+                Some(Action::ScrollDown) => view.scroll_by(1),
+                Some(Action::ScrollUp) => view.scroll_by(-1),
+                Some(Action::PageDown) => view.scroll_pages(1),
+                Some(Action::PageUp) => view.scroll_pages(-1),
+                Some(Action::NextMatch) => view.find.select_by(1),
+                Some(Action::PrevMatch) => view.find.select_by(-1),
+                // show it in the queue, without playing it
+                Some(Action::Open) => {
+                    view.focus = view.find.position();
+                    view.toggle(View::Find);
+                }
+                _ => return,
+            },
+        }
+        drop(view);
+        refresh(conn);
+    }
+
+    fn play(pos: usize, conn: &mut Client) {
+        if let Ok(pos) = u32::try_from(pos) {
+            let _ = conn.switch(pos);
+        }
+    }
+
+    // keys while typing a search: the query changes as you type, and the
+    // results under it can still be scrolled
+    fn handle_search(&self, key: Key, conn: &mut Client) {
+        if key == Key::Enter {
+            self.search_now(conn);
+            return;
+        }
+        let mut view = self.view.lock().expect("can't lock view");
+        match key {
+            Key::Esc => view.toggle(View::Search),
+            Key::Backspace => {
+                view.search.query.pop();
+                view.search.generation += 1;
+                self.search_later(view.search.generation);
+            }
+            Key::Char(c) if !c.is_control() => {
+                view.search.query.push(c);
+                view.search.generation += 1;
+                self.search_later(view.search.generation);
+            }
+            _ => match self.keymap.get(key) {
+                Some(Action::ScrollDown) => view.scroll_by(1),
+                Some(Action::ScrollUp) => view.scroll_by(-1),
+                Some(Action::PageDown) => view.scroll_pages(1),
+                Some(Action::PageUp) => view.scroll_pages(-1),
+                _ => return,
+            },
+        }
+        drop(view);
+        refresh(conn);
+    }
+
+    // go to the results, without waiting for the pause if they're not in
+    // yet, so keys after this one are about them
+    fn search_now(&self, conn: &mut Client) {
+        let mut view = self.view.lock().expect("can't lock view");
+        view.search.generation += 1;
+        let generation = view.search.generation;
+        drop(view);
+
+        let (done, wait) = mpsc::channel();
+        if self.searches().send(Job::Now(generation, done)).is_ok() {
+            let _ = wait.recv();
+        }
+        refresh(conn);
+    }
+
+    // search once typing stops for a moment, unless the query has changed
+    // again by then
+    fn search_later(&self, generation: u64) {
+        let _ = self.searches().send(Job::Later(generation, Instant::now()));
+    }
+
+    // the search thread, started the first time it's needed
+    fn searches(&self) -> &Sender<Job> {
+        self.searches.get_or_init(|| {
+            search::worker(
+                SharedView::clone(&self.view),
+                self.address.clone(),
+                Arc::clone(&self.client),
+            )
+        })
     }
 
     fn inc_rating(inc: i8, conn: &mut Client) {
@@ -283,22 +829,22 @@ impl KeyHandler {
     }
 }
 
-fn getch() -> Result<char, io::Error> {
+fn getkey() -> Result<Key, io::Error> {
     let stdin = 0;
     let backup_termios =
         Termios::from_fd(stdin).expect("can't get file descriptor");
 
     // call this as a function so that we can always reset termios
-    let ch = getch_raw();
+    let key = getkey_raw();
 
     // reset the stdin to original termios data
     tcsetattr(stdin, TCSANOW, &backup_termios)
         .expect("can't set terminal attributes");
 
-    ch
+    key
 }
 
-fn getch_raw() -> Result<char, io::Error> {
+fn getkey_raw() -> Result<Key, io::Error> {
     let stdin = 0;
     let mut termios =
         Termios::from_fd(stdin).expect("can't get file descriptor");
@@ -309,10 +855,50 @@ fn getch_raw() -> Result<char, io::Error> {
     let stdout = io::stdout();
     let mut reader = io::stdin();
 
-    // read exactly one byte
+    // wait for one byte
     let mut buffer = [0; 1];
     stdout.lock().flush()?;
     reader.read_exact(&mut buffer)?;
 
-    Ok(buffer[0].into())
+    match buffer[0] {
+        b'\n' | b'\r' => return Ok(Key::Enter),
+        b'\t' => return Ok(Key::Tab),
+        b'\x08' | b'\x7f' => return Ok(Key::Backspace),
+        b'\x1b' => {}
+        // ascii, or the start of a longer utf-8 character
+        b if b.is_ascii() => return Ok(Key::Char(b.into())),
+        b => return read_utf8(b, &mut reader),
+    }
+
+    // an escape on its own is the esc key, otherwise there's a sequence
+    // right behind it. give it a tenth of a second to show up.
+    termios.c_cc[VMIN] = 0;
+    termios.c_cc[VTIME] = 1;
+    tcsetattr(stdin, TCSANOW, &termios)?;
+    let mut seq = [0; 4];
+    let len = reader.read(&mut seq)?;
+
+    Ok(match &seq[..len] {
+        b"[A" | b"OA" => Key::Up,
+        b"[B" | b"OB" => Key::Down,
+        b"[C" | b"OC" => Key::Right,
+        b"[D" | b"OD" => Key::Left,
+        b"[5~" => Key::PageUp,
+        b"[6~" => Key::PageDown,
+        b"[H" | b"OH" | b"[1~" | b"[7~" => Key::Home,
+        b"[F" | b"OF" | b"[4~" | b"[8~" => Key::End,
+        b"[Z" => Key::BackTab,
+        _ => Key::Esc,
+    })
+}
+
+fn read_utf8(first: u8, reader: &mut io::Stdin) -> Result<Key, io::Error> {
+    // the leading ones say how many bytes follow
+    let len = first.leading_ones() as usize;
+    let mut buffer = vec![first; len.clamp(1, 4)];
+    reader.read_exact(&mut buffer[1..])?;
+    Ok(std::str::from_utf8(&buffer)
+        .ok()
+        .and_then(|s| s.chars().next())
+        .map_or(Key::Char(char::REPLACEMENT_CHARACTER), Key::Char))
 }
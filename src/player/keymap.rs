@@ -0,0 +1,186 @@
+use crate::common::{Action, Bind, Key};
+
+// the keys everything is bound to, unless the user says otherwise
+const DEFAULTS: [(&str, Action); 41] = [
+    ("h?/", Action::Help),
+    ("qQ", Action::Quit),
+    (" ", Action::Toggle),
+    ("pk", Action::Prev),
+    ("nj", Action::Next),
+    ("H", Action::SeekBack),
+    ("L", Action::SeekAhead),
+    ("M", Action::Stop),
+    ("=+0)", Action::VolumeUp),
+    ("-_9(", Action::VolumeDown),
+    ("x", Action::CrossfadeUp),
+    ("X", Action::CrossfadeDown),
+    ("E", Action::Repeat),
+    ("R", Action::Random),
+    ("S", Action::Single),
+    ("C", Action::Consume),
+    ("F", Action::Shuffle),
+    ("}", Action::RateUp),
+    ("{", Action::RateDown),
+    ("!", Action::ClearError),
+    ("l", Action::Lyrics),
+    ("v", Action::Layout),
+    ("w", Action::WhereAmI),
+    ("J", Action::ScrollDown),
+    ("K", Action::ScrollUp),
+    ("z", Action::Collapse),
+    ("t", Action::Find),
+    ("b", Action::Library),
+    ("s", Action::Search),
+    ("f", Action::Files),
+    ("g", Action::Locate),
+    ("P", Action::Playlists),
+    ("W", Action::SavePlaylist),
+    ("e", Action::Rename),
+    ("d", Action::Remove),
+    ("<", Action::MoveUp),
+    (">", Action::MoveDown),
+    ("a", Action::Add),
+    ("A", Action::AddAll),
+    ("i", Action::Insert),
+    ("r", Action::Replace),
+];
+
+// keys without a character of their own
+const DEFAULT_KEYS: [(Key, Action); 11] = [
+    (Key::Down, Action::ScrollDown),
+    (Key::Up, Action::ScrollUp),
+    (Key::PageDown, Action::PageDown),
+    (Key::PageUp, Action::PageUp),
+    (Key::Enter, Action::Open),
+    (Key::Right, Action::Open),
+    (Key::Backspace, Action::Back),
+    (Key::Left, Action::Back),
+    (Key::Esc, Action::Back),
+    (Key::Tab, Action::NextMatch),
+    (Key::BackTab, Action::PrevMatch),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    General,
+    Playback,
+    Volume,
+    Options,
+    Rating,
+    Scrolling,
+    Browsing,
+    Playlists,
+    Finding,
+}
+
+impl Category {
+    pub const ALL: [Self; 9] = [
+        Self::General,
+        Self::Playback,
+        Self::Volume,
+        Self::Options,
+        Self::Rating,
+        Self::Scrolling,
+        Self::Browsing,
+        Self::Playlists,
+        Self::Finding,
+    ];
+
+    pub const fn of(action: Action) -> Self {
+        match action {
+            Action::Help
+            | Action::Quit
+            | Action::Lyrics
+            | Action::Layout
+            | Action::WhereAmI
+            | Action::ClearError => Self::General,
+            Action::Toggle
+            | Action::Prev
+            | Action::Next
+            | Action::SeekBack
+            | Action::SeekAhead
+            | Action::Stop => Self::Playback,
+            Action::VolumeUp
+            | Action::VolumeDown
+            | Action::CrossfadeUp
+            | Action::CrossfadeDown => Self::Volume,
+            Action::Repeat
+            | Action::Random
+            | Action::Single
+            | Action::Consume
+            | Action::Shuffle => Self::Options,
+            Action::RateUp | Action::RateDown => Self::Rating,
+            Action::ScrollDown
+            | Action::ScrollUp
+            | Action::PageDown
+            | Action::PageUp
+            | Action::Collapse => Self::Scrolling,
+            Action::Library
+            | Action::Search
+            | Action::Files
+            | Action::Locate
+            | Action::Open
+            | Action::Back
+            | Action::Add
+            | Action::AddAll
+            | Action::Insert
+            | Action::Replace => Self::Browsing,
+            Action::Playlists
+            | Action::SavePlaylist
+            | Action::Rename
+            | Action::Remove
+            | Action::MoveUp
+            | Action::MoveDown => Self::Playlists,
+            Action::Find | Action::NextMatch | Action::PrevMatch => {
+                Self::Finding
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Keymap {
+    // in the order they should be listed, with whether the user set them
+    bindings: Vec<(Key, Action, bool)>,
+}
+
+impl Keymap {
+    #[must_use]
+    pub fn new(binds: &[Bind]) -> Self {
+        let mut bindings = DEFAULTS
+            .iter()
+            .flat_map(|(keys, action)| {
+                keys.chars().map(|c| (Key::Char(c), *action, false))
+            })
+            .chain(DEFAULT_KEYS.iter().map(|(k, a)| (*k, *a, false)))
+            .collect::<Vec<_>>();
+
+        // a key can only do one thing, so the user's bindings replace ours
+        for bind in binds {
+            bindings.retain(|(k, _, _)| *k != bind.key);
+            bindings.push((bind.key, bind.action, true));
+        }
+        Self { bindings }
+    }
+
+    // unbind every key for actions that don't pass `keep`
+    pub fn retain(&mut self, keep: impl Fn(Action) -> bool) {
+        self.bindings.retain(|(_, a, _)| keep(*a));
+    }
+
+    pub fn get(&self, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, a, _)| *a)
+    }
+
+    // every key for an action, and whether the user bound it
+    pub fn keys(&self, action: Action) -> Vec<(Key, bool)> {
+        self.bindings
+            .iter()
+            .filter(|(_, a, _)| *a == action)
+            .map(|(k, _, user)| (*k, *user))
+            .collect()
+    }
+}
@@ -0,0 +1,155 @@
+// the mpd crate doesn't cover every command we need, so this is a tiny
+// client that speaks the protocol directly, on its own connection.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use mpd::Song;
+
+type Pairs = Vec<(String, String)>;
+
+// a server that stops answering shouldn't hang whoever asked
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct RawClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl RawClient {
+    pub fn connect(address: &str) -> Result<Self, String> {
+        let stream = TcpStream::connect(address).map_err(|e| e.to_string())?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .and_then(|()| stream.set_write_timeout(Some(TIMEOUT)))
+            .map_err(|e| e.to_string())?;
+        let writer = stream.try_clone().map_err(|e| e.to_string())?;
+        let mut client = Self {
+            reader: BufReader::new(stream),
+            writer,
+        };
+
+        // check the greeting
+        let banner = client.read_line()?;
+        if !banner.starts_with("OK MPD") {
+            return Err(format!("unexpected greeting: {banner}"));
+        }
+        Ok(client)
+    }
+
+    // run a command, and get back every line of its response
+    pub fn command(
+        &mut self,
+        name: &str,
+        args: &[&str],
+    ) -> Result<Pairs, String> {
+        self.send(name, args)?;
+
+        let mut pairs = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            if line == "OK" {
+                break;
+            }
+            pairs.push(Self::parse_line(&line)?);
+        }
+        Ok(pairs)
+    }
+
+    // run a command whose response has a binary chunk in it
+    pub fn binary(
+        &mut self,
+        name: &str,
+        args: &[&str],
+    ) -> Result<(Pairs, Vec<u8>), String> {
+        self.send(name, args)?;
+
+        let mut pairs = Vec::new();
+        let mut data = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            if line == "OK" {
+                break;
+            }
+            let (key, value) = Self::parse_line(&line)?;
+            if key == "binary" {
+                let size = value.parse().map_err(|_| "bad binary size")?;
+                data.resize(size, 0);
+                self.reader
+                    .read_exact(&mut data)
+                    .map_err(|e| e.to_string())?;
+                // skip the newline after the data
+                self.read_line()?;
+            }
+            pairs.push((key, value));
+        }
+        Ok((pairs, data))
+    }
+
+    fn send(&mut self, name: &str, args: &[&str]) -> Result<(), String> {
+        let mut line = name.to_string();
+        for arg in args {
+            line.push(' ');
+            line.push_str(&quote(arg));
+        }
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .map_err(|e| e.to_string())
+    }
+
+    fn parse_line(line: &str) -> Result<(String, String), String> {
+        // errors look like "ACK [50@0] {command} message"
+        if let Some(err) = line.strip_prefix("ACK ") {
+            let message = err.split_once("} ").map_or(err, |(_, m)| m);
+            return Err(message.into());
+        }
+        line.split_once(": ")
+            .map(|(k, v)| (k.into(), v.into()))
+            .ok_or_else(|| format!("bad response: {line}"))
+    }
+
+    fn read_line(&mut self) -> Result<String, String> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Err("connection closed".into()),
+            Ok(_) => Ok(line.trim_end_matches('\n').into()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+// songs from a response, each one starting at its "file" line, read the
+// same way the mpd crate reads them
+pub fn songs(pairs: Pairs) -> Vec<Song> {
+    let mut songs: Vec<Song> = Vec::new();
+    for (key, value) in pairs {
+        if key == "file" {
+            songs.push(Song {
+                file: value,
+                ..Song::default()
+            });
+            continue;
+        }
+        let Some(song) = songs.last_mut() else {
+            continue;
+        };
+        match key.as_str() {
+            "Title" => song.title = Some(value),
+            "Artist" => song.artist = Some(value),
+            "Name" => song.name = Some(value),
+            "Last-Modified" => song.last_mod = Some(value),
+            "Time" => {
+                song.duration = value.parse().ok().map(Duration::from_secs);
+            }
+            _ => song.tags.push((key, value)),
+        }
+    }
+    songs
+}
+
+fn quote(arg: &str) -> String {
+    let escaped = arg.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
@@ -0,0 +1,71 @@
+use std::thread;
+use std::time::Duration;
+
+use mpd::{message::Channel, Client};
+use signal_hook::consts::SIGWINCH;
+use signal_hook::iterator::Signals;
+use uuid::Uuid;
+
+#[allow(unused_imports)]
+use debug_print::{
+    debug_eprint as deprint, debug_eprintln as deprintln,
+    debug_print as dprint, debug_println as dprintln,
+};
+
+pub struct ResizeHandler {
+    address: String,
+    uuid: Uuid,
+}
+
+impl ResizeHandler {
+    #[must_use]
+    pub fn new(address: &str, uuid: Uuid) -> Self {
+        Self {
+            address: address.into(),
+            uuid,
+        }
+    }
+
+    pub fn init(&self) {
+        // how long the terminal has to stay still before we redraw
+        const DEBOUNCE: Duration = Duration::from_millis(50);
+
+        let mut signals =
+            Signals::new([SIGWINCH]).expect("can't register SIGWINCH");
+
+        loop {
+            // block until we get at least one resize
+            if signals.wait().count() == 0 {
+                continue;
+            }
+
+            // dragging a window sends a flood of signals, so wait until
+            // they stop arriving before doing anything
+            loop {
+                thread::sleep(DEBOUNCE);
+                if signals.pending().count() == 0 {
+                    break;
+                }
+            }
+
+            dprintln!("[resize]");
+            self.redraw();
+        }
+    }
+
+    fn redraw(&self) {
+        // use a fresh connection every time, so we never have to worry
+        // about the server timing out an unused client
+        let Ok(mut conn) = Client::connect(&self.address) else {
+            return;
+        };
+
+        // toggle resize channel to force idle break
+        let chan =
+            Channel::new(format!("resize_{}", self.uuid.simple()).as_str())
+                .expect("can't make resize channel");
+        let _ = conn.subscribe(chan.clone());
+        let _ = conn.unsubscribe(chan);
+        let _ = conn.close();
+    }
+}
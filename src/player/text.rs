@@ -0,0 +1,202 @@
+use crate::common::Align;
+
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: char = '…';
+const ESCAPE: char = '\x1b';
+const VARIATION: char = '\u{fe0f}';
+
+// number of terminal columns a string takes up, ignoring ansi codes
+pub fn width(s: &str) -> usize {
+    strip_ansi(s).width()
+}
+
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch == ESCAPE {
+            skip_escape(&mut chars, |_| {});
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+// cut a string down to `max` columns, ending it with an ellipsis if anything
+// was removed. ansi codes are always kept, so colors still get reset.
+pub fn truncate(s: &str, max: usize) -> String {
+    if width(s) <= max {
+        return s.into();
+    }
+
+    // leave room for the ellipsis
+    let limit = max.saturating_sub(1);
+    let mut out = String::with_capacity(s.len());
+    let mut used = 0;
+    let mut done = max == 0;
+
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == ESCAPE {
+            out.push(ch);
+            skip_escape(&mut chars, |c| out.push(c));
+            continue;
+        }
+        if done {
+            continue;
+        }
+
+        // emoji with a variation selector are wider than their base char
+        let cell = if chars.peek() == Some(&VARIATION) {
+            chars.next();
+            format!("{ch}{VARIATION}")
+        } else {
+            ch.to_string()
+        };
+        let w = cell.width();
+        if used + w > limit {
+            out.push(ELLIPSIS);
+            done = true;
+            continue;
+        }
+        used += w;
+        out.push_str(&cell);
+    }
+    out
+}
+
+// fit a string into exactly `max` columns, truncating or padding as needed
+pub fn align(s: &str, max: usize, align: Align) -> String {
+    let s = truncate(s, max);
+    let space = max.saturating_sub(width(&s));
+    let (left, right) = match align {
+        Align::Left => (0, space),
+        Align::Center => (space / 2, space - space / 2),
+        Align::Right => (space, 0),
+    };
+    format!("{}{s}{}", " ".repeat(left), " ".repeat(right))
+}
+
+// the columns of a string from `start`, at most `max` of them. wide
+// characters cut in half by either edge become spaces, and ansi codes are
+// always kept.
+pub fn slice(s: &str, start: usize, max: usize) -> String {
+    let end = start + max;
+    let mut out = String::with_capacity(s.len());
+    let mut col = 0;
+
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == ESCAPE {
+            out.push(ch);
+            skip_escape(&mut chars, |c| out.push(c));
+            continue;
+        }
+
+        let cell = if chars.peek() == Some(&VARIATION) {
+            chars.next();
+            format!("{ch}{VARIATION}")
+        } else {
+            ch.to_string()
+        };
+        let next = col + cell.width();
+        if col >= start && next <= end {
+            out.push_str(&cell);
+        } else if next > start && col < end {
+            out.push_str(&" ".repeat(next.min(end) - col.max(start)));
+        }
+        col = next;
+    }
+    out
+}
+
+// consume the rest of an escape sequence, passing each char to `f`
+fn skip_escape<I, F>(chars: &mut I, mut f: F)
+where
+    I: Iterator<Item = char>,
+    F: FnMut(char),
+{
+    let Some(next) = chars.next() else {
+        return;
+    };
+    f(next);
+    // CSI sequences end with a byte in the range '@'..='~'
+    if next == '[' {
+        for c in chars.by_ref() {
+            f(c);
+            if ('@'..='~').contains(&c) {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn width_ignores_ansi_and_counts_wide_chars() {
+        assert_eq!(width("\x1b[1;34mabc\x1b[0m"), 3);
+        assert_eq!(width("東京"), 4);
+        assert_eq!(width("☀\u{fe0f}"), 2);
+    }
+
+    #[test]
+    fn truncate_leaves_short_strings_alone() {
+        assert_eq!(truncate("abc", 3), "abc");
+        assert_eq!(truncate("", 0), "");
+    }
+
+    #[test]
+    fn truncate_ends_with_an_ellipsis() {
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("abcdef", 1), "…");
+        assert_eq!(truncate("abcdef", 0), "");
+    }
+
+    #[test]
+    fn truncate_never_splits_a_wide_char() {
+        // "東" would need columns 2 and 3, with only 2 left before the "…"
+        assert_eq!(truncate("a東京", 3), "a…");
+        assert_eq!(width(&truncate("東京東京", 5)), 5);
+    }
+
+    #[test]
+    fn truncate_keeps_ansi_codes() {
+        let cut = truncate("\x1b[1mabcdef\x1b[0m", 4);
+        assert_eq!(cut, "\x1b[1mabc…\x1b[0m");
+        assert_eq!(width(&cut), 4);
+    }
+
+    #[test]
+    fn align_pads_to_the_width() {
+        assert_eq!(align("ab", 5, Align::Left), "ab   ");
+        assert_eq!(align("ab", 5, Align::Right), "   ab");
+        assert_eq!(align("ab", 5, Align::Center), " ab  ");
+        assert_eq!(align("abcdef", 4, Align::Right), "abc…");
+    }
+
+    #[test]
+    fn slice_takes_columns_from_the_start() {
+        assert_eq!(slice("abcdef", 0, 3), "abc");
+        assert_eq!(slice("abcdef", 2, 3), "cde");
+        assert_eq!(slice("abcdef", 4, 5), "ef");
+        assert_eq!(slice("abc", 5, 2), "");
+    }
+
+    #[test]
+    fn slice_turns_halves_of_wide_chars_into_spaces() {
+        // "東" is columns 0-1 and "京" is 2-3
+        assert_eq!(slice("東京", 1, 2), "  ");
+        assert_eq!(slice("東京", 1, 3), " 京");
+        assert_eq!(slice("東京", 0, 3), "東 ");
+    }
+
+    #[test]
+    fn slice_keeps_ansi_codes_outside_the_window() {
+        assert_eq!(slice("\x1b[1mabc\x1b[0mdef", 4, 2), "\x1b[1m\x1b[0mef");
+    }
+}
@@ -0,0 +1,252 @@
+use super::browse::playlists::Playlists;
+use super::browse::search::Search;
+use super::browse::Browser;
+use super::find::Find;
+use crate::common::{Action, Key, Layout};
+
+use std::collections::HashSet;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use clap::ValueEnum;
+
+// what the screen is showing, besides the header
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    #[default]
+    Queue,
+    Lyrics,
+    Help,
+    Library,
+    Search,
+    Files,
+    Playlists,
+    Find,
+}
+
+impl View {
+    // views that are lists to pick things from
+    pub const fn is_browser(self) -> bool {
+        matches!(
+            self,
+            Self::Library | Self::Search | Self::Files | Self::Playlists
+        )
+    }
+}
+
+// state shared between the input and display threads. input changes it,
+// then forces the display out of idle so it gets redrawn.
+#[derive(Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ViewState {
+    pub view: View,
+    // where to go back to when the view is closed
+    pub prev: View,
+    // lines scrolled down from the top, for views that scroll by hand
+    pub scroll: usize,
+    // how many lines fit on the screen, so input knows how far a page is
+    pub page: usize,
+    // whether the queue is grouped by album, and how many groups away from
+    // the one that's playing we are
+    pub group: bool,
+    pub cursor: isize,
+    // album groups that are collapsed, and whether the one under the cursor
+    // should be flipped next time we draw
+    pub collapsed: HashSet<String>,
+    pub collapse: bool,
+    // what's been typed to narrow down the help, and whether keys go there
+    pub filter: String,
+    pub filtering: bool,
+    // the layout asked for, which might not fit
+    pub layout: Layout,
+    // whether to say where we are next time, in accessible mode
+    pub where_am_i: bool,
+    pub library: Browser,
+    pub search: Search,
+    pub files: Browser,
+    pub playlists: Playlists,
+    pub find: Find,
+    // a song in the queue to show instead of the one that's playing
+    pub focus: Option<usize>,
+}
+
+pub type SharedView = Arc<Mutex<ViewState>>;
+
+// what a key did in the help screen
+#[derive(Debug, PartialEq, Eq)]
+pub enum HelpKey {
+    Quit,
+    Changed,
+    Ignored,
+}
+
+impl ViewState {
+    pub fn toggle(&mut self, view: View) {
+        if self.view == view {
+            self.view = mem::take(&mut self.prev);
+        } else {
+            self.prev = self.view;
+            self.view = view;
+        }
+        self.scroll = 0;
+        self.filter.clear();
+        self.filtering = false;
+    }
+
+    pub fn next_layout(&mut self) {
+        let layouts = Layout::value_variants();
+        let i = layouts.iter().position(|l| *l == self.layout).unwrap_or(0);
+        self.layout = layouts[(i + 1) % layouts.len()];
+    }
+
+    pub const fn browser(&self) -> Option<&Browser> {
+        match self.view {
+            View::Library => Some(&self.library),
+            View::Search => Some(&self.search.results),
+            View::Files => Some(&self.files),
+            View::Playlists => Some(&self.playlists.browser),
+            _ => None,
+        }
+    }
+
+    pub const fn browser_mut(&mut self) -> Option<&mut Browser> {
+        match self.view {
+            View::Library => Some(&mut self.library),
+            View::Search => Some(&mut self.search.results),
+            View::Files => Some(&mut self.files),
+            View::Playlists => Some(&mut self.playlists.browser),
+            _ => None,
+        }
+    }
+
+    // move down (or up, if negative) in whatever we're showing
+    pub fn scroll_by(&mut self, lines: isize) {
+        match self.view {
+            View::Queue => {
+                // only album groups have a cursor to move
+                if self.group {
+                    self.cursor += lines;
+                }
+                self.focus = self.focus.map(|f| f.saturating_add_signed(lines));
+            }
+            View::Find => self.find.move_by(lines),
+            View::Lyrics | View::Help => {
+                self.scroll = self.scroll.saturating_add_signed(lines);
+            }
+            View::Library | View::Search | View::Files | View::Playlists => {
+                if let Some(browser) = self.browser_mut() {
+                    browser.move_by(lines);
+                }
+            }
+        }
+    }
+
+    pub fn scroll_pages(&mut self, pages: isize) {
+        // album groups move one at a time, however big the page is
+        let page = match self.view {
+            View::Queue => 1,
+            _ => self.page.max(1).try_into().unwrap_or(isize::MAX),
+        };
+        self.scroll_by(pages.saturating_mul(page));
+    }
+
+    // keys in the help screen mostly go to the filter. once something's
+    // being typed, even the quit key does.
+    pub fn help_key(&mut self, key: Key, action: Option<Action>) -> HelpKey {
+        match key {
+            Key::Esc if self.filtering => {
+                self.filter.clear();
+                self.filtering = false;
+            }
+            Key::Char(c) if self.filtering && !c.is_control() => {
+                self.filter.push(c);
+            }
+            _ if action == Some(Action::Quit) => return HelpKey::Quit,
+            Key::Esc => self.toggle(View::Help),
+            Key::Backspace => {
+                self.filter.pop();
+            }
+            // like less, for filters that start with a help key
+            Key::Char('/') => self.filtering = true,
+            Key::Char(_) if action == Some(Action::Help) => {
+                self.toggle(View::Help);
+            }
+            Key::Char(c) if !c.is_control() => {
+                self.filter.push(c);
+                self.filtering = true;
+                self.scroll = 0;
+            }
+            Key::Home => self.scroll = 0,
+            Key::End => self.scroll = usize::MAX,
+            _ => match action {
+                Some(Action::ScrollDown) => self.scroll_by(1),
+                Some(Action::ScrollUp) => self.scroll_by(-1),
+                Some(Action::PageDown) => self.scroll_pages(1),
+                Some(Action::PageUp) => self.scroll_pages(-1),
+                _ => return HelpKey::Ignored,
+            },
+        }
+        HelpKey::Changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // type keys into the help screen, the way the default keymap has them
+    fn help(keys: &str) -> (ViewState, Vec<HelpKey>) {
+        let mut view = ViewState::default();
+        view.toggle(View::Help);
+        let done = keys
+            .chars()
+            .map(|c| {
+                let action = match c {
+                    'q' => Some(Action::Quit),
+                    'h' => Some(Action::Help),
+                    _ => None,
+                };
+                view.help_key(Key::Char(c), action)
+            })
+            .collect();
+        (view, done)
+    }
+
+    #[test]
+    fn quits_when_not_typing() {
+        let (_, done) = help("q");
+        assert_eq!(done, [HelpKey::Quit]);
+    }
+
+    #[test]
+    fn typing_takes_the_quit_key() {
+        let (view, done) = help("/queue");
+        assert!(!done.contains(&HelpKey::Quit));
+        assert_eq!(view.filter, "queue");
+        assert_eq!(view.view, View::Help);
+
+        let (view, done) = help("uq");
+        assert!(!done.contains(&HelpKey::Quit));
+        assert_eq!(view.filter, "uq");
+    }
+
+    #[test]
+    fn help_keys_start_a_filter_after_a_slash() {
+        let (view, _) = help("h");
+        assert_eq!(view.view, View::Queue);
+
+        let (view, _) = help("/h");
+        assert_eq!(view.view, View::Help);
+        assert_eq!(view.filter, "h");
+    }
+
+    #[test]
+    fn esc_clears_the_filter_then_closes() {
+        let (mut view, _) = help("abc");
+        assert_eq!(view.help_key(Key::Esc, None), HelpKey::Changed);
+        assert!(view.filter.is_empty());
+        assert_eq!(view.view, View::Help);
+        assert_eq!(view.help_key(Key::Esc, None), HelpKey::Changed);
+        assert_eq!(view.view, View::Queue);
+    }
+}